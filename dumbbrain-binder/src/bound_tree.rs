@@ -1,10 +1,89 @@
+use std::collections::HashMap;
+
 use dumbbrain::object::DumbBrainObject;
+use dumbbrain::span::Span;
 use dumbbrain::types::DumbBrainType;
+use dumbbrain_lexer::syntax::SyntaxKind;
+
+use crate::conversion::convert;
+use crate::symbols::FunctionSymbol;
+use crate::symbols::VariableSymbol;
+
+/// Everything bound from one compilation unit: its top-level statements,
+/// and the body of every function it declares. Both are lowered into a flat
+/// block by the `Lowerer`.
+#[derive(Debug)]
+pub struct BoundProgram {
+    pub statement: BoundStatement,
+    pub functions: HashMap<FunctionSymbol, BoundStatement>,
+}
 
 #[derive(Debug)]
 pub struct BoundExpression {
     pub node: BoundExpressionNode,
     pub kind: DumbBrainType,
+    pub span: Span,
+    /// The value of the expression, if it is known without running the
+    /// program.
+    pub constant: Option<DumbBrainObject>,
+}
+
+impl BoundExpression {
+    /// Creates an expression, replacing it with a literal if its value is
+    /// constant. Since operands are created first, constant subtrees fold
+    /// bottom-up into a single literal.
+    pub fn new(node: BoundExpressionNode, kind: DumbBrainType, span: Span) -> Self {
+        let constant = match &node {
+            BoundExpressionNode::Literal { value } => value.clone(),
+            BoundExpressionNode::Binary {
+                left,
+                right,
+                operation,
+            } => match (operation, &left.constant, &right.constant) {
+                // The right operand would not run, so it need not be constant.
+                (BinaryOperation::LogicalAnd, Some(DumbBrainObject::Boolean(false)), _)
+                | (BinaryOperation::LogicalOr, Some(DumbBrainObject::Boolean(true)), _) => {
+                    left.constant.clone()
+                }
+                // Whether a float division by zero fails is up to the
+                // evaluator, so it is left for it to run.
+                (
+                    BinaryOperation::Divide
+                    | BinaryOperation::Modulo
+                    | BinaryOperation::FloorDivide,
+                    _,
+                    Some(DumbBrainObject::Float(divisor)),
+                ) if *divisor == 0.0 => None,
+                (_, Some(left), Some(right)) => operation.apply(left, right).ok(),
+                _ => None,
+            },
+            BoundExpressionNode::Unary { operand, operation } => operand
+                .constant
+                .as_ref()
+                .and_then(|operand| operation.apply(operand).ok()),
+            BoundExpressionNode::Conversion { expression } => expression
+                .constant
+                .as_ref()
+                .and_then(|value| convert(value, kind).ok()),
+            BoundExpressionNode::Variable { variable } => variable.constant.clone(),
+            BoundExpressionNode::Assignment { .. }
+            | BoundExpressionNode::PostfixAssignment { .. }
+            | BoundExpressionNode::Call { .. }
+            | BoundExpressionNode::Error => None,
+        };
+        let node = match &constant {
+            Some(value) => BoundExpressionNode::Literal {
+                value: Some(value.clone()),
+            },
+            None => node,
+        };
+        Self {
+            node,
+            kind,
+            span,
+            constant,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -13,6 +92,17 @@ pub enum BinaryOperation {
     Subtract,
     Multiply,
     Divide,
+    /// The remainder of `FloorDivide`, which has the sign of the divisor.
+    Modulo,
+    /// Division rounding towards negative infinity.
+    FloorDivide,
+    Power,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    /// Shifts right, copying the sign bit into the vacated bits.
+    ShiftRight,
 
     Equality,
     Inequality,
@@ -22,12 +112,56 @@ pub enum BinaryOperation {
     GreaterEquals,
     LogicalAnd,
     LogicalOr,
+    LogicalXor,
+}
+
+impl BinaryOperation {
+    /// The operator token this operation is written with.
+    pub fn syntax_kind(self) -> SyntaxKind {
+        match self {
+            Self::Add => SyntaxKind::PlusToken,
+            Self::Subtract => SyntaxKind::MinusToken,
+            Self::Multiply => SyntaxKind::StarToken,
+            Self::Divide => SyntaxKind::SlashToken,
+            Self::Modulo => SyntaxKind::PercentToken,
+            Self::FloorDivide => SyntaxKind::TildeSlashToken,
+            Self::Power => SyntaxKind::StarStarToken,
+            Self::BitwiseAnd => SyntaxKind::AmpersandToken,
+            Self::BitwiseOr => SyntaxKind::PipeToken,
+            Self::BitwiseXor => SyntaxKind::HatToken,
+            Self::ShiftLeft => SyntaxKind::LessLessToken,
+            Self::ShiftRight => SyntaxKind::GreaterGreaterToken,
+            Self::Equality => SyntaxKind::EqualsEqualsToken,
+            Self::Inequality => SyntaxKind::BangEqualsToken,
+            Self::Less => SyntaxKind::LessToken,
+            Self::LessEquals => SyntaxKind::LessEqualsToken,
+            Self::Greater => SyntaxKind::GreaterToken,
+            Self::GreaterEquals => SyntaxKind::GreaterEqualsToken,
+            Self::LogicalAnd => SyntaxKind::AmpersandAmpersandToken,
+            Self::LogicalOr => SyntaxKind::PipePipeToken,
+            Self::LogicalXor => SyntaxKind::HatHatToken,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum UnaryOperation {
     Identity,
     Negation,
+    LogicalNegation,
+    BitwiseNot,
+}
+
+impl UnaryOperation {
+    /// The operator token this operation is written with.
+    pub fn syntax_kind(self) -> SyntaxKind {
+        match self {
+            Self::Identity => SyntaxKind::PlusToken,
+            Self::Negation => SyntaxKind::MinusToken,
+            Self::LogicalNegation => SyntaxKind::BangToken,
+            Self::BitwiseNot => SyntaxKind::TildeToken,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -44,4 +178,90 @@ pub enum BoundExpressionNode {
         operand: Box<BoundExpression>,
         operation: UnaryOperation,
     },
+    Variable {
+        variable: VariableSymbol,
+    },
+    Assignment {
+        variable: VariableSymbol,
+        expression: Box<BoundExpression>,
+    },
+    /// Assigns like `Assignment`, but evaluates to the value the variable
+    /// had before. `x++` binds to this with `x + 1` as the expression.
+    PostfixAssignment {
+        variable: VariableSymbol,
+        expression: Box<BoundExpression>,
+    },
+    Call {
+        function: FunctionSymbol,
+        arguments: Vec<BoundExpression>,
+    },
+    /// Converts the value of `expression` to the type of this expression.
+    Conversion {
+        expression: Box<BoundExpression>,
+    },
+    /// An expression that could not be bound. Its type is always
+    /// `DumbBrainType::Error`.
+    Error,
+}
+
+/// A position in a lowered block that `Goto` and `ConditionalGoto` jump to.
+/// Labels are unique within one block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoundLabel {
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct BoundStatement {
+    pub node: BoundStatementNode,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub enum BoundStatementNode {
+    Block {
+        statements: Vec<BoundStatement>,
+    },
+    Expression {
+        expression: BoundExpression,
+    },
+    VariableDeclaration {
+        variable: VariableSymbol,
+        initializer: BoundExpression,
+    },
+    If {
+        condition: BoundExpression,
+        then_statement: Box<BoundStatement>,
+        else_statement: Option<Box<BoundStatement>>,
+    },
+    While {
+        condition: BoundExpression,
+        body: Box<BoundStatement>,
+    },
+    /// Runs `body` with `variable` set to every int from `lower_bound` up
+    /// to and including `upper_bound`.
+    For {
+        variable: VariableSymbol,
+        lower_bound: BoundExpression,
+        upper_bound: BoundExpression,
+        body: Box<BoundStatement>,
+    },
+    Break,
+    Continue,
+    Return {
+        expression: Option<BoundExpression>,
+    },
+    Label {
+        label: BoundLabel,
+    },
+    Goto {
+        label: BoundLabel,
+    },
+    /// Jumps to `label` if `condition` evaluates to `jump_if_true`, and
+    /// otherwise falls through to the next statement.
+    ConditionalGoto {
+        label: BoundLabel,
+        condition: BoundExpression,
+        jump_if_true: bool,
+    },
 }
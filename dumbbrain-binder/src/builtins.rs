@@ -0,0 +1,50 @@
+use std::sync::OnceLock;
+
+use dumbbrain::types::DumbBrainType;
+
+use crate::symbols::FunctionSymbol;
+use crate::symbols::VariableSymbol;
+
+/// `error(message: string)`, which stops the program with `message`.
+pub fn error_function() -> &'static FunctionSymbol {
+    static ERROR: OnceLock<FunctionSymbol> = OnceLock::new();
+    ERROR.get_or_init(|| {
+        FunctionSymbol::new(
+            String::from("error"),
+            vec![VariableSymbol::new(
+                String::from("message"),
+                true,
+                true,
+                DumbBrainType::String,
+                None,
+            )],
+            DumbBrainType::Void,
+        )
+    })
+}
+
+/// `approxEq(a: float, b: float, tolerance: float): bool`, which compares
+/// floats allowing for rounding errors: whether `a` and `b` differ by at most
+/// `tolerance`.
+pub fn approx_eq_function() -> &'static FunctionSymbol {
+    static APPROX_EQ: OnceLock<FunctionSymbol> = OnceLock::new();
+    APPROX_EQ.get_or_init(|| {
+        let parameter = |name: &str| {
+            VariableSymbol::new(String::from(name), true, true, DumbBrainType::Float, None)
+        };
+        FunctionSymbol::new(
+            String::from("approxEq"),
+            vec![parameter("a"), parameter("b"), parameter("tolerance")],
+            DumbBrainType::Boolean,
+        )
+    })
+}
+
+/// The function called `name` that every program can use without declaring
+/// it, if there is one. The evaluator implements these itself.
+pub fn lookup_builtin(name: &str) -> Option<&'static FunctionSymbol> {
+    [error_function(), approx_eq_function()]
+        .iter()
+        .copied()
+        .find(|function| function.name == name)
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use dumbbrain::object::DumbBrainObject;
+use dumbbrain::span::Span;
+
+use crate::bound_tree::*;
+
+/// A run of statements that always execute together: only its first
+/// statement is jumped to, and only its last one jumps away.
+#[derive(Debug, Default)]
+pub struct BasicBlock<'a> {
+    pub statements: Vec<&'a BoundStatement>,
+}
+
+/// The condition under which control moves along a branch.
+#[derive(Debug, Clone, Copy)]
+pub struct BranchCondition<'a> {
+    pub expression: &'a BoundExpression,
+    /// The value `expression` must have for the branch to be taken.
+    pub value: bool,
+}
+
+/// An edge between two basic blocks, given by their indices.
+#[derive(Debug)]
+pub struct BasicBlockBranch<'a> {
+    pub from: usize,
+    pub to: usize,
+    pub condition: Option<BranchCondition<'a>>,
+}
+
+/// The control-flow graph of a lowered block. The first basic block is an
+/// empty entry and the last one an empty exit, which every `return` and the
+/// end of the block lead to.
+#[derive(Debug)]
+pub struct ControlFlowGraph<'a> {
+    pub blocks: Vec<BasicBlock<'a>>,
+    pub branches: Vec<BasicBlockBranch<'a>>,
+}
+
+impl<'a> ControlFlowGraph<'a> {
+    /// Builds the graph of a block produced by the `Lowerer`.
+    pub fn create(body: &'a BoundStatement) -> Self {
+        let statements = match &body.node {
+            BoundStatementNode::Block { statements } => statements,
+            _ => unreachable!("lowered trees are always blocks"),
+        };
+
+        let mut blocks = vec![BasicBlock::default()];
+        let mut current = BasicBlock::default();
+        for statement in statements {
+            if let BoundStatementNode::Label { .. } = statement.node {
+                if !current.statements.is_empty() {
+                    blocks.push(std::mem::take(&mut current));
+                }
+            }
+            current.statements.push(statement);
+            if let BoundStatementNode::Goto { .. }
+            | BoundStatementNode::ConditionalGoto { .. }
+            | BoundStatementNode::Return { .. } = statement.node
+            {
+                blocks.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.statements.is_empty() {
+            blocks.push(current);
+        }
+        blocks.push(BasicBlock::default());
+
+        let end = blocks.len() - 1;
+        let labels = blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, block)| match &block.statements.first()?.node {
+                BoundStatementNode::Label { label } => Some((label, index)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut branches = vec![BasicBlockBranch {
+            from: 0,
+            to: 1,
+            condition: None,
+        }];
+        for (from, block) in blocks.iter().enumerate().take(end).skip(1) {
+            let next = from + 1;
+            let branch = |to, condition| BasicBlockBranch {
+                from,
+                to,
+                condition,
+            };
+            match block.statements.last().map(|statement| &statement.node) {
+                Some(BoundStatementNode::Goto { label }) => {
+                    branches.push(branch(labels[label], None));
+                }
+                Some(BoundStatementNode::ConditionalGoto {
+                    label,
+                    condition,
+                    jump_if_true,
+                }) => {
+                    // A constant condition only ever takes one of the branches.
+                    let targets = [(labels[label], *jump_if_true), (next, !*jump_if_true)];
+                    for (to, value) in targets.iter().copied() {
+                        match condition.constant {
+                            Some(DumbBrainObject::Boolean(constant)) if constant != value => {}
+                            _ => branches.push(branch(
+                                to,
+                                Some(BranchCondition {
+                                    expression: condition,
+                                    value,
+                                }),
+                            )),
+                        }
+                    }
+                }
+                Some(BoundStatementNode::Return { .. }) => branches.push(branch(end, None)),
+                _ => branches.push(branch(next, None)),
+            }
+        }
+
+        Self { blocks, branches }
+    }
+
+    /// Which basic blocks can be reached from the entry, by index.
+    pub fn reachable_blocks(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut pending = vec![0];
+        while let Some(block) = pending.pop() {
+            if reachable[block] {
+                continue;
+            }
+            reachable[block] = true;
+            pending.extend(
+                self.branches
+                    .iter()
+                    .filter(|branch| branch.from == block)
+                    .map(|branch| branch.to),
+            );
+        }
+        reachable
+    }
+
+    /// Whether every way of reaching the exit goes through a `return`.
+    pub fn all_paths_return(&self) -> bool {
+        let reachable = self.reachable_blocks();
+        let end = self.blocks.len() - 1;
+        self.branches
+            .iter()
+            .filter(|branch| branch.to == end && reachable[branch.from])
+            .all(|branch| {
+                matches!(
+                    self.blocks[branch.from].statements.last(),
+                    Some(BoundStatement {
+                        node: BoundStatementNode::Return { .. },
+                        ..
+                    })
+                )
+            })
+    }
+
+    /// The source spans of every run of code that can never execute.
+    ///
+    /// The `Lowerer` gives the statements it introduces the span of the
+    /// statement they were lowered from, and some of those, such as the
+    /// `goto` past the `else` of an `if` whose branches both return, are
+    /// unreachable on their own. So a span is only unreachable if no
+    /// statement with that span can be reached.
+    pub fn unreachable_code(&self) -> Vec<Span> {
+        let reachable = self.reachable_blocks();
+        let reachable_spans = self
+            .blocks
+            .iter()
+            .zip(&reachable)
+            .filter(|(_, reachable)| **reachable)
+            .flat_map(|(block, _)| &block.statements)
+            .map(|statement| &statement.span)
+            .collect::<Vec<_>>();
+
+        let mut regions = vec![];
+        let mut region: Option<Span> = None;
+        for (block, reachable) in self.blocks.iter().zip(&reachable) {
+            if *reachable {
+                regions.extend(region.take());
+                continue;
+            }
+            for statement in &block.statements {
+                if let BoundStatementNode::Label { .. } = statement.node {
+                    continue;
+                }
+                if reachable_spans.contains(&&statement.span) {
+                    continue;
+                }
+                region = Some(match region {
+                    Some(region) => region.join(&statement.span),
+                    None => statement.span.clone(),
+                });
+            }
+        }
+        regions.extend(region);
+        regions
+    }
+
+    /// Renders the graph in Graphviz's DOT language, with one box per basic
+    /// block and each branch labelled with its condition.
+    pub fn to_dot(&self) -> String {
+        let end = self.blocks.len() - 1;
+        let mut dot = String::from("digraph G {\n");
+        for (index, block) in self.blocks.iter().enumerate() {
+            let label = if index == 0 {
+                String::from("<Start>")
+            } else if index == end {
+                String::from("<End>")
+            } else {
+                block
+                    .statements
+                    .iter()
+                    .map(|statement| escape(statement.to_string().trim()) + "\\l")
+                    .collect()
+            };
+            dot += &format!("    N{} [label = \"{}\", shape = box]\n", index, label);
+        }
+        for branch in &self.branches {
+            dot += &format!("    N{} -> N{}", branch.from, branch.to);
+            if let Some(condition) = branch.condition {
+                let label = if condition.value {
+                    condition.expression.to_string()
+                } else {
+                    format!("!({})", condition.expression)
+                };
+                dot += &format!(" [label = \"{}\"]", escape(&label));
+            }
+            dot += "\n";
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use dumbbrain_parser::Parser;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::*;
+    use crate::Binder;
+
+    fn check(input: &str, expected: Expect) {
+        let tree = Parser::new(input).parse();
+        let program = Binder::new().bind_compilation_unit(&tree.root);
+        let body = program.functions.values().next().unwrap();
+        expected.assert_eq(&ControlFlowGraph::create(body).to_dot());
+    }
+
+    #[test]
+    fn export_if_else() {
+        check(
+            "function sign(n: int): string { if n < 0 return \"-\" else if n > 0 return \"+\"\nreturn \"\" }",
+            expect![[r#"
+                digraph G {
+                    N0 [label = "<Start>", shape = box]
+                    N1 [label = "goto else1 unless n < 0\l", shape = box]
+                    N2 [label = "return \"-\"\l", shape = box]
+                    N3 [label = "goto end2\l", shape = box]
+                    N4 [label = "else1:\lgoto end3 unless n > 0\l", shape = box]
+                    N5 [label = "return \"+\"\l", shape = box]
+                    N6 [label = "end3:\l", shape = box]
+                    N7 [label = "end2:\lreturn \"\"\l", shape = box]
+                    N8 [label = "<End>", shape = box]
+                    N0 -> N1
+                    N1 -> N4 [label = "!(n < 0)"]
+                    N1 -> N2 [label = "n < 0"]
+                    N2 -> N8
+                    N3 -> N7
+                    N4 -> N6 [label = "!(n > 0)"]
+                    N4 -> N5 [label = "n > 0"]
+                    N5 -> N8
+                    N6 -> N7
+                    N7 -> N8
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn export_while() {
+        check(
+            "function count(n: int) { var i = 0\nwhile i < n i = i + 1 }",
+            expect![[r#"
+                digraph G {
+                    N0 [label = "<Start>", shape = box]
+                    N1 [label = "var i = 0\lgoto continue2\l", shape = box]
+                    N2 [label = "body1:\li = i + 1\l", shape = box]
+                    N3 [label = "continue2:\lgoto body1 if i < n\l", shape = box]
+                    N4 [label = "break3:\l", shape = box]
+                    N5 [label = "<End>", shape = box]
+                    N0 -> N1
+                    N1 -> N3
+                    N2 -> N3
+                    N3 -> N2 [label = "i < n"]
+                    N3 -> N4 [label = "!(i < n)"]
+                    N4 -> N5
+                }
+            "#]],
+        )
+    }
+}
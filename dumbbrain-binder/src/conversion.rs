@@ -0,0 +1,147 @@
+use std::num::IntErrorKind;
+use std::num::ParseIntError;
+
+use dumbbrain::object::DumbBrainObject;
+use dumbbrain::types::DumbBrainType;
+
+use crate::operators::OperationError;
+
+/// Whether, and where, a value of one type may be turned into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    /// The types are the same.
+    Identity,
+    /// Happens wherever a value of the target type is expected, because it
+    /// always succeeds.
+    Implicit,
+    /// Only happens when written out, as in `int(x)`, because it can lose
+    /// information or fail.
+    Explicit,
+    /// There is no conversion between the types.
+    None,
+}
+
+impl Conversion {
+    pub fn classify(from: DumbBrainType, to: DumbBrainType) -> Self {
+        match (from, to) {
+            _ if from == to => Self::Identity,
+            (DumbBrainType::Int, DumbBrainType::Float) => Self::Implicit,
+            (DumbBrainType::Float, DumbBrainType::Int) => Self::Explicit,
+            // Every value can be written as a string, and parsing one back
+            // can fail.
+            (
+                DumbBrainType::Boolean | DumbBrainType::Int | DumbBrainType::Float,
+                DumbBrainType::String,
+            )
+            | (
+                DumbBrainType::String,
+                DumbBrainType::Boolean | DumbBrainType::Int | DumbBrainType::Float,
+            ) => Self::Explicit,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Converts `value` to `kind`. Floats become ints by rounding towards zero,
+/// and those out of the range of an int, including infinities and NaN,
+/// overflow. Strings are parsed, and must hold nothing but the value.
+pub fn convert(
+    value: &DumbBrainObject,
+    kind: DumbBrainType,
+) -> Result<DumbBrainObject, OperationError> {
+    match (value, kind) {
+        (value, kind) if value.kind() == kind => Ok(value.clone()),
+        (DumbBrainObject::Int(n), DumbBrainType::Float) => Ok(DumbBrainObject::Float(*n as f64)),
+        (DumbBrainObject::Float(n), DumbBrainType::Int) => {
+            // Both bounds are powers of two, so they are exact as floats.
+            if *n >= i64::MIN as f64 && *n < -(i64::MIN as f64) {
+                Ok(DumbBrainObject::Int(*n as i64))
+            } else {
+                Err(OperationError::Overflow)
+            }
+        }
+        // Strings are handled above, so these are written without quotes.
+        (value, DumbBrainType::String) => Ok(DumbBrainObject::String(value.to_string())),
+        (DumbBrainObject::String(s), DumbBrainType::Int) => s
+            .parse()
+            .map(DumbBrainObject::Int)
+            .map_err(|error| match (error as ParseIntError).kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => OperationError::Overflow,
+                _ => OperationError::InvalidFormat,
+            }),
+        (DumbBrainObject::String(s), DumbBrainType::Float) => s
+            .parse()
+            .map(DumbBrainObject::Float)
+            .map_err(|_| OperationError::InvalidFormat),
+        (DumbBrainObject::String(s), DumbBrainType::Boolean) => match s.as_str() {
+            "true" => Ok(DumbBrainObject::Boolean(true)),
+            "false" => Ok(DumbBrainObject::Boolean(false)),
+            _ => Err(OperationError::InvalidFormat),
+        },
+        _ => Err(OperationError::TypeMismatch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floats_convert_to_ints_in_range() {
+        let to_int = |n: f64| convert(&DumbBrainObject::Float(n), DumbBrainType::Int);
+        assert_eq!(to_int(-2.7), Ok(DumbBrainObject::Int(-2)));
+        assert_eq!(to_int(i64::MIN as f64), Ok(DumbBrainObject::Int(i64::MIN)));
+        assert_eq!(to_int(-(i64::MIN as f64)), Err(OperationError::Overflow));
+        assert_eq!(to_int(f64::NAN), Err(OperationError::Overflow));
+    }
+
+    #[test]
+    fn strings_are_parsed_strictly() {
+        let from_string = |s: &str, kind| convert(&DumbBrainObject::String(String::from(s)), kind);
+        assert_eq!(
+            from_string("-42", DumbBrainType::Int),
+            Ok(DumbBrainObject::Int(-42))
+        );
+        assert_eq!(
+            from_string(" 42", DumbBrainType::Int),
+            Err(OperationError::InvalidFormat)
+        );
+        assert_eq!(
+            from_string("9223372036854775808", DumbBrainType::Int),
+            Err(OperationError::Overflow)
+        );
+        assert_eq!(
+            from_string("2.5e3", DumbBrainType::Float),
+            Ok(DumbBrainObject::Float(2500.0))
+        );
+        assert_eq!(
+            from_string("2.5.3", DumbBrainType::Float),
+            Err(OperationError::InvalidFormat)
+        );
+        assert_eq!(
+            from_string("false", DumbBrainType::Boolean),
+            Ok(DumbBrainObject::Boolean(false))
+        );
+        assert_eq!(
+            from_string("False", DumbBrainType::Boolean),
+            Err(OperationError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn values_convert_to_strings_without_quotes() {
+        let to_string = |value| convert(&value, DumbBrainType::String);
+        assert_eq!(
+            to_string(DumbBrainObject::Float(3.0)),
+            Ok(DumbBrainObject::String(String::from("3.0")))
+        );
+        assert_eq!(
+            to_string(DumbBrainObject::Boolean(true)),
+            Ok(DumbBrainObject::String(String::from("true")))
+        );
+        assert_eq!(
+            to_string(DumbBrainObject::String(String::from("a"))),
+            Ok(DumbBrainObject::String(String::from("a")))
+        );
+    }
+}
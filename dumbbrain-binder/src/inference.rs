@@ -0,0 +1,274 @@
+use std::convert::TryFrom;
+
+use dumbbrain::diagnostic::Diagnostic;
+use dumbbrain::diagnostic::DiagnosticCode;
+use dumbbrain::span::Span;
+use dumbbrain::types::DumbBrainType;
+
+/// A constraint that decided the type of a type variable.
+#[derive(Debug, Clone)]
+struct Constraint {
+    kind: DumbBrainType,
+    span: Span,
+    reason: String,
+}
+
+/// Solves equality constraints between types by unification. Variables that
+/// are unified form a set, which takes its type from the first constraint
+/// that gives one to any of its members.
+#[derive(Debug, Default)]
+pub struct TypeUnifier {
+    /// The variable each variable was unified with. The variable that
+    /// represents a set is its own parent.
+    parents: Vec<u32>,
+    /// What each variable is the type of, like "parameter 'x'".
+    names: Vec<String>,
+    /// The constraint that decided the type of each represented set.
+    bindings: Vec<Option<Constraint>>,
+    /// Every unification of two variables, which explains how a conflict
+    /// reaches the variable it is reported for.
+    links: Vec<(u32, u32, Span, String)>,
+}
+
+impl TypeUnifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new variable for the type of `name`.
+    pub fn fresh(&mut self, name: String) -> DumbBrainType {
+        let variable = u32::try_from(self.parents.len()).expect("too many type variables");
+        self.parents.push(variable);
+        self.names.push(name);
+        self.bindings.push(None);
+        DumbBrainType::Variable(variable)
+    }
+
+    fn find(&mut self, variable: u32) -> u32 {
+        let parent = self.parents[variable as usize];
+        if parent == variable {
+            return variable;
+        }
+        let root = self.find(parent);
+        self.parents[variable as usize] = root;
+        root
+    }
+
+    /// The type decided for `kind` so far, or the variable that represents
+    /// it.
+    pub fn resolve(&mut self, kind: DumbBrainType) -> DumbBrainType {
+        match kind {
+            DumbBrainType::Variable(variable) => {
+                let root = self.find(variable);
+                match &self.bindings[root as usize] {
+                    Some(constraint) => constraint.kind,
+                    None => DumbBrainType::Variable(root),
+                }
+            }
+            _ => kind,
+        }
+    }
+
+    /// Requires `a` and `b` to be the same type, because of `reason` at
+    /// `span`. Two different known types are left for the binder to report,
+    /// and the error type is the same as every other type. A variable whose
+    /// constraints conflict becomes the error type, so that the binder does
+    /// not report the conflict again.
+    pub fn unify(
+        &mut self,
+        a: DumbBrainType,
+        b: DumbBrainType,
+        span: &Span,
+        reason: &str,
+    ) -> Result<(), Diagnostic> {
+        let constraint = |kind| Constraint {
+            kind,
+            span: span.clone(),
+            reason: reason.to_string(),
+        };
+        match (a, b) {
+            (DumbBrainType::Variable(a), DumbBrainType::Variable(b)) => {
+                let (a_root, b_root) = (self.find(a), self.find(b));
+                if a_root == b_root {
+                    return Ok(());
+                }
+                self.links.push((a, b, span.clone(), reason.to_string()));
+                self.parents[a_root as usize] = b_root;
+                match (
+                    self.bindings[a_root as usize].clone(),
+                    self.bindings[b_root as usize].clone(),
+                ) {
+                    (Some(first), Some(second)) if !compatible(first.kind, second.kind) => {
+                        let diagnostic = self.conflict(a, &first, &second);
+                        self.bindings[b_root as usize] = Some(constraint(DumbBrainType::Error));
+                        Err(diagnostic)
+                    }
+                    (first, second) => {
+                        self.bindings[b_root as usize] = second.or(first);
+                        Ok(())
+                    }
+                }
+            }
+            (DumbBrainType::Variable(variable), kind)
+            | (kind, DumbBrainType::Variable(variable)) => {
+                let root = self.find(variable);
+                match self.bindings[root as usize].clone() {
+                    None => {
+                        self.bindings[root as usize] = Some(constraint(kind));
+                        Ok(())
+                    }
+                    Some(existing) if compatible(existing.kind, kind) => Ok(()),
+                    Some(existing) => {
+                        let diagnostic = self.conflict(variable, &existing, &constraint(kind));
+                        self.bindings[root as usize] = Some(constraint(DumbBrainType::Error));
+                        Err(diagnostic)
+                    }
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The variable that represents the set `kind` belongs to, or `kind`
+    /// itself if it is not a variable.
+    pub fn representative(&mut self, kind: DumbBrainType) -> DumbBrainType {
+        match kind {
+            DumbBrainType::Variable(variable) => DumbBrainType::Variable(self.find(variable)),
+            _ => kind,
+        }
+    }
+
+    /// What the variable `kind` is the type of.
+    pub fn name(&self, kind: DumbBrainType) -> &str {
+        match kind {
+            DumbBrainType::Variable(variable) => &self.names[variable as usize],
+            _ => "",
+        }
+    }
+
+    /// Makes the variable `kind` the error type, once a mistake that its type
+    /// explains has been reported.
+    pub fn set_error(&mut self, kind: DumbBrainType, span: &Span) {
+        if let DumbBrainType::Variable(variable) = kind {
+            let root = self.find(variable);
+            self.bindings[root as usize] = Some(Constraint {
+                kind: DumbBrainType::Error,
+                span: span.clone(),
+                reason: String::new(),
+            });
+        }
+    }
+
+    /// Labels `diagnostic` with the constraint that decided the type of the
+    /// variable `kind`, and the unifications that connect the two.
+    pub fn explain(&mut self, mut diagnostic: Diagnostic, kind: DumbBrainType) -> Diagnostic {
+        if let DumbBrainType::Variable(variable) = kind {
+            let root = self.find(variable);
+            if let Some(constraint) = self.bindings[root as usize].clone() {
+                diagnostic = diagnostic.with_label(constraint.span.clone(), constraint.label());
+            }
+            diagnostic = self.label_links(diagnostic, variable);
+        }
+        diagnostic
+    }
+
+    fn conflict(&mut self, variable: u32, first: &Constraint, second: &Constraint) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(
+            DiagnosticCode::CannotInfer,
+            second.span.clone(),
+            format!(
+                "conflicting types for {}: {} and {}",
+                self.names[variable as usize], first.kind, second.kind
+            ),
+        );
+        for constraint in [first, second].iter() {
+            diagnostic = diagnostic.with_label(constraint.span.clone(), constraint.label());
+        }
+        self.label_links(diagnostic, variable)
+    }
+
+    fn label_links(&mut self, mut diagnostic: Diagnostic, variable: u32) -> Diagnostic {
+        let root = self.find(variable);
+        for (a, b, span, reason) in self.links.clone() {
+            if self.find(a) == root {
+                diagnostic = diagnostic.with_label(
+                    span,
+                    format!(
+                        "{} and {} have the same type because {}",
+                        self.names[a as usize], self.names[b as usize], reason
+                    ),
+                );
+            }
+        }
+        diagnostic
+    }
+}
+
+impl Constraint {
+    fn label(&self) -> String {
+        format!("inferred as {} because {}", self.kind, self.reason)
+    }
+}
+
+fn compatible(a: DumbBrainType, b: DumbBrainType) -> bool {
+    a == b || a == DumbBrainType::Error || b == DumbBrainType::Error
+}
+
+/// What the binder keeps while it infers the types of a lambda, by binding
+/// its body with type variables for the types it leaves out.
+#[derive(Debug, Default)]
+pub(crate) struct LambdaInference {
+    pub(crate) unifier: TypeUnifier,
+    /// Whether any `return` in the body has a value.
+    pub(crate) returns_value: bool,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(column: usize) -> Span {
+        Span {
+            first_line: 1,
+            first_column: column,
+            last_line: 1,
+            last_column: column,
+        }
+    }
+
+    #[test]
+    fn unified_variables_share_their_type() {
+        let mut unifier = TypeUnifier::new();
+        let a = unifier.fresh(String::from("a"));
+        let b = unifier.fresh(String::from("b"));
+        let int = DumbBrainType::Int;
+        assert!(unifier.unify(a, b, &span(1), "a is b").is_ok());
+        assert_eq!(unifier.resolve(a), unifier.resolve(b));
+        assert!(unifier.unify(b, int, &span(2), "b is an int").is_ok());
+        assert_eq!(unifier.resolve(a), int);
+    }
+
+    #[test]
+    fn conflicts_name_both_constraints() {
+        let mut unifier = TypeUnifier::new();
+        let a = unifier.fresh(String::from("parameter 'a'"));
+        let b = unifier.fresh(String::from("parameter 'b'"));
+        let int = DumbBrainType::Int;
+        let string = DumbBrainType::String;
+        unifier.unify(a, int, &span(1), "it is added to 1").unwrap();
+        unifier
+            .unify(b, string, &span(2), "it is added to \"\"")
+            .unwrap();
+        let error = unifier
+            .unify(a, b, &span(3), "they are compared")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "error[DB0028] at 1:2: conflicting types for parameter 'a': int and string\n  \
+             1:1: inferred as int because it is added to 1\n  \
+             1:2: inferred as string because it is added to \"\"\n  \
+             1:3: parameter 'a' and parameter 'b' have the same type because they are compared"
+        );
+    }
+}
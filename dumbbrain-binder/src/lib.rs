@@ -1,16 +1,793 @@
+use std::collections::HashMap;
+
+use dumbbrain::diagnostic::Diagnostic;
+use dumbbrain::diagnostic::DiagnosticBag;
+use dumbbrain::diagnostic::DiagnosticCode;
+use dumbbrain::object::DumbBrainObject;
+use dumbbrain::span::Span;
 use dumbbrain::types::DumbBrainType;
 use dumbbrain_lexer::syntax::SyntaxKind;
 use dumbbrain_lexer::token::Token;
 use dumbbrain_parser::ast::traits::SyntaxNode;
+use dumbbrain_parser::ast::CompilationUnitSyntax;
 use dumbbrain_parser::ast::ExpressionSyntax;
+use dumbbrain_parser::ast::MemberSyntax;
+use dumbbrain_parser::ast::ParameterSyntax;
+use dumbbrain_parser::ast::SeparatedSyntaxList;
+use dumbbrain_parser::ast::StatementSyntax;
+use dumbbrain_parser::ast::TypeClauseSyntax;
 
 mod bound_tree;
 pub use bound_tree::*;
+mod builtins;
+pub use builtins::*;
+mod control_flow;
+pub use control_flow::*;
+mod conversion;
+pub use conversion::*;
+mod inference;
+pub use inference::*;
+mod lowerer;
+pub use lowerer::*;
+mod operators;
+pub use operators::*;
+mod printer;
+mod scope;
+pub use scope::*;
+mod symbols;
+pub use symbols::*;
 
-pub struct Binder {}
+#[derive(Default)]
+pub struct Binder {
+    diagnostics: DiagnosticBag,
+    scope: BoundScope,
+    /// How many loops enclose the statement being bound.
+    loop_depth: usize,
+    /// The function whose body is being bound, if any.
+    function: Option<FunctionSymbol>,
+    /// The bodies of the lambdas bound so far, which become functions of the
+    /// program.
+    lambdas: HashMap<FunctionSymbol, BoundStatement>,
+    /// The locals of the function around the lambda being bound. A lambda
+    /// is called with its own frame, so it cannot use them.
+    outer_locals: Vec<VariableSymbol>,
+    /// The types being inferred for the lambda whose body is being bound
+    /// with type variables, if any.
+    inference: Option<LambdaInference>,
+}
 
 impl Binder {
-    pub fn bind_expression(expression: &ExpressionSyntax) -> BoundExpression {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a binder that sees, and adds to, the symbols in `scope`.
+    pub fn with_scope(scope: BoundScope) -> Self {
+        Self {
+            scope,
+            ..Self::default()
+        }
+    }
+
+    pub fn diagnostics(&self) -> &DiagnosticBag {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> DiagnosticBag {
+        self.diagnostics
+    }
+
+    pub fn into_scope(self) -> BoundScope {
+        self.scope
+    }
+
+    /// Binds the top-level statements in the binder's own scope, so that
+    /// the symbols they declare remain in `into_scope`.
+    ///
+    /// Functions can be called before their declaration, so all of them are
+    /// declared first. Their bodies are bound in order with the statements,
+    /// and see the global variables declared before them.
+    pub fn bind_compilation_unit(&mut self, unit: &CompilationUnitSyntax) -> BoundProgram {
+        let mut declared_functions = vec![];
+        for member in &unit.members {
+            if let MemberSyntax::FunctionDeclaration {
+                identifier_token,
+                parameters,
+                type_clause,
+                ..
+            } = member
+            {
+                declared_functions.push(self.bind_function_declaration(
+                    identifier_token,
+                    parameters,
+                    type_clause.as_ref(),
+                ));
+            }
+        }
+
+        let mut declared_functions = declared_functions.into_iter();
+        let mut statements = vec![];
+        let mut functions = HashMap::new();
+        for member in &unit.members {
+            match member {
+                MemberSyntax::FunctionDeclaration {
+                    identifier_token,
+                    parameters,
+                    body,
+                    ..
+                } => {
+                    let function = declared_functions
+                        .next()
+                        .expect("every function declaration was bound");
+                    let body = self.bind_function_body(&function, parameters, body);
+                    let body = Lowerer::lower(body);
+                    self.check_control_flow(&body, Some((&function, identifier_token)));
+                    functions.insert(function, body);
+                }
+                MemberSyntax::GlobalStatement { statement } => {
+                    statements.push(self.bind_statement(statement));
+                }
+            }
+        }
+
+        let statement = Lowerer::lower(BoundStatement {
+            node: BoundStatementNode::Block { statements },
+            span: unit.span(),
+        });
+        self.check_control_flow(&statement, None);
+        functions.extend(self.lambdas.drain());
+        BoundProgram {
+            statement,
+            functions,
+        }
+    }
+
+    /// Warns about code in a lowered block that can never run, and, for the
+    /// body of a function that returns a value, reports paths that end
+    /// without a `return`.
+    fn check_control_flow(
+        &mut self,
+        body: &BoundStatement,
+        function: Option<(&FunctionSymbol, &Token)>,
+    ) {
+        let graph = ControlFlowGraph::create(body);
+        if let Some((function, identifier_token)) = function {
+            let returns_value = !matches!(
+                function.return_type,
+                DumbBrainType::Void | DumbBrainType::Error
+            );
+            if returns_value && !graph.all_paths_return() {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::MissingReturn,
+                    identifier_token.span.clone(),
+                    String::from("not all code paths return a value"),
+                ));
+            }
+        }
+        for span in graph.unreachable_code() {
+            self.diagnostics.report(Diagnostic::warning(
+                DiagnosticCode::UnreachableCode,
+                span,
+                String::from("unreachable code"),
+            ));
+        }
+    }
+
+    fn bind_function_declaration(
+        &mut self,
+        identifier_token: &Token,
+        parameters: &SeparatedSyntaxList<ParameterSyntax>,
+        type_clause: Option<&TypeClauseSyntax>,
+    ) -> FunctionSymbol {
+        let mut parameter_symbols = vec![];
+        for parameter in parameters.iter() {
+            let kind = match &parameter.type_clause {
+                Some(type_clause) => self.bind_type_clause(type_clause),
+                // Only lambdas leave out the types of their parameters, and
+                // the parser reports it anywhere else.
+                None => DumbBrainType::Error,
+            };
+            parameter_symbols.push(VariableSymbol::new(
+                parameter.identifier_token.text.clone(),
+                true,
+                true,
+                kind,
+                Some(parameter.identifier_token.span.clone()),
+            ));
+        }
+        let return_type = match type_clause {
+            Some(type_clause) => self.bind_type_clause(type_clause),
+            None => DumbBrainType::Void,
+        };
+
+        let function = FunctionSymbol::new(
+            identifier_token.text.clone(),
+            parameter_symbols,
+            return_type,
+        );
+        // A missing name has already been reported by the parser.
+        if !identifier_token.text.is_empty() && !self.scope.try_declare_function(function.clone()) {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::AlreadyDeclared,
+                identifier_token.span.clone(),
+                format!("function '{}' is already declared", identifier_token.text),
+            ));
+        }
+        function
+    }
+
+    fn bind_type_clause(&mut self, type_clause: &TypeClauseSyntax) -> DumbBrainType {
+        let identifier_token = &type_clause.identifier_token;
+        if identifier_token.text.is_empty() {
+            return DumbBrainType::Error;
+        }
+        DumbBrainType::lookup(&identifier_token.text).unwrap_or_else(|| {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::UndefinedType,
+                identifier_token.span.clone(),
+                format!("undefined type '{}'", identifier_token.text),
+            ));
+            DumbBrainType::Error
+        })
+    }
+
+    fn bind_function_body(
+        &mut self,
+        function: &FunctionSymbol,
+        parameters: &SeparatedSyntaxList<ParameterSyntax>,
+        body: &StatementSyntax,
+    ) -> BoundStatement {
+        self.push_scope();
+        for (symbol, parameter) in function.parameters.iter().zip(parameters.iter()) {
+            let identifier_token = &parameter.identifier_token;
+            if !identifier_token.text.is_empty() && !self.scope.try_declare(symbol.clone()) {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::AlreadyDeclared,
+                    identifier_token.span.clone(),
+                    format!("parameter '{}' is already declared", identifier_token.text),
+                ));
+            }
+        }
+
+        // Lambdas are bound inside other bodies, and loops around them do
+        // not continue into them.
+        let outer_function = self.function.replace(function.clone());
+        let outer_loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.bind_statement(body);
+        self.function = outer_function;
+        self.loop_depth = outer_loop_depth;
+        self.pop_scope();
+        body
+    }
+
+    pub fn bind_statement(&mut self, statement: &StatementSyntax) -> BoundStatement {
+        let node = match statement {
+            StatementSyntax::Block { statements, .. } => self.bind_block_statement(statements),
+            StatementSyntax::Expression { expression } => BoundStatementNode::Expression {
+                expression: self.bind_expression(expression),
+            },
+            StatementSyntax::VariableDeclaration {
+                keyword_token,
+                identifier_token,
+                type_clause,
+                initializer,
+                ..
+            } => self.bind_variable_declaration(
+                keyword_token,
+                identifier_token,
+                type_clause.as_ref(),
+                initializer,
+            ),
+            StatementSyntax::If {
+                condition,
+                then_statement,
+                else_clause,
+                ..
+            } => BoundStatementNode::If {
+                condition: self.bind_condition(condition),
+                then_statement: Box::new(self.bind_statement(then_statement)),
+                else_statement: else_clause
+                    .as_ref()
+                    .map(|clause| Box::new(self.bind_statement(&clause.else_statement))),
+            },
+            StatementSyntax::While {
+                condition, body, ..
+            } => BoundStatementNode::While {
+                condition: self.bind_condition(condition),
+                body: Box::new(self.bind_loop_body(body)),
+            },
+            StatementSyntax::For {
+                identifier_token,
+                lower_bound,
+                upper_bound,
+                body,
+                ..
+            } => self.bind_for_statement(identifier_token, lower_bound, upper_bound, body),
+            StatementSyntax::Break { break_keyword } => {
+                self.check_inside_loop(break_keyword);
+                BoundStatementNode::Break
+            }
+            StatementSyntax::Continue { continue_keyword } => {
+                self.check_inside_loop(continue_keyword);
+                BoundStatementNode::Continue
+            }
+            StatementSyntax::Return {
+                return_keyword,
+                expression,
+            } => self.bind_return_statement(return_keyword, expression.as_ref()),
+        };
+        BoundStatement {
+            node,
+            span: statement.span(),
+        }
+    }
+
+    fn bind_block_statement(&mut self, statements: &[StatementSyntax]) -> BoundStatementNode {
+        self.push_scope();
+        let statements = statements
+            .iter()
+            .map(|statement| self.bind_statement(statement))
+            .collect();
+        self.pop_scope();
+        BoundStatementNode::Block { statements }
+    }
+
+    fn push_scope(&mut self) {
+        let parent = std::mem::take(&mut self.scope);
+        self.scope = BoundScope::with_parent(parent);
+    }
+
+    fn pop_scope(&mut self) {
+        let scope = std::mem::take(&mut self.scope);
+        self.scope = scope
+            .into_parent()
+            .expect("pushed scopes always have a parent");
+    }
+
+    /// Binds a condition, and warns if it always has the same value even
+    /// though it was not written as a literal or a single name, which are
+    /// taken to be deliberate.
+    fn bind_condition(&mut self, syntax: &ExpressionSyntax) -> BoundExpression {
+        let condition = self.bind_expression_of_type(syntax, DumbBrainType::Boolean, "condition");
+        if let Some(DumbBrainObject::Boolean(value)) = condition.constant {
+            if is_computed(syntax) {
+                self.diagnostics.report(Diagnostic::warning(
+                    DiagnosticCode::ConstantCondition,
+                    condition.span.clone(),
+                    format!("condition is always {}", value),
+                ));
+            }
+        }
+        condition
+    }
+
+    /// Binds `expression` and reports an error, naming it by `role`, unless
+    /// it has type `kind`.
+    fn bind_expression_of_type(
+        &mut self,
+        expression: &ExpressionSyntax,
+        kind: DumbBrainType,
+        role: &str,
+    ) -> BoundExpression {
+        let expression = self.bind_expression(expression);
+        self.require_type(expression.kind, kind, &expression.span, || {
+            format!("it is a {}", role)
+        });
+        let expression = implicit_conversion(expression, kind);
+        if expression.kind != kind && expression.kind != DumbBrainType::Error {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::TypeMismatch,
+                expression.span.clone(),
+                format!(
+                    "expected {} of type {}, found {}",
+                    role, kind, expression.kind
+                ),
+            ));
+        }
+        expression
+    }
+
+    fn bind_loop_body(&mut self, body: &StatementSyntax) -> BoundStatement {
+        self.loop_depth += 1;
+        let body = self.bind_statement(body);
+        self.loop_depth -= 1;
+        body
+    }
+
+    /// The loop variable lives in a scope of its own around the body and
+    /// cannot be assigned to.
+    fn bind_for_statement(
+        &mut self,
+        identifier_token: &Token,
+        lower_bound: &ExpressionSyntax,
+        upper_bound: &ExpressionSyntax,
+        body: &StatementSyntax,
+    ) -> BoundStatementNode {
+        let lower_bound =
+            self.bind_expression_of_type(lower_bound, DumbBrainType::Int, "lower bound");
+        let upper_bound =
+            self.bind_expression_of_type(upper_bound, DumbBrainType::Int, "upper bound");
+
+        self.push_scope();
+        let variable = self.declare_variable(identifier_token, true, DumbBrainType::Int, None);
+        let body = self.bind_loop_body(body);
+        self.pop_scope();
+
+        BoundStatementNode::For {
+            variable,
+            lower_bound,
+            upper_bound,
+            body: Box::new(body),
+        }
+    }
+
+    fn check_inside_loop(&mut self, keyword: &Token) {
+        if self.loop_depth == 0 {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::NotInsideLoop,
+                keyword.span.clone(),
+                format!("'{}' is not inside a loop", keyword.text),
+            ));
+        }
+    }
+
+    fn bind_return_statement(
+        &mut self,
+        return_keyword: &Token,
+        expression: Option<&ExpressionSyntax>,
+    ) -> BoundStatementNode {
+        let expression = expression.map(|expression| self.bind_expression(expression));
+        if let Some(return_type) = self.function.as_ref().map(|function| function.return_type) {
+            let (kind, span, reason) = match &expression {
+                Some(expression) => (
+                    expression.kind,
+                    return_keyword.span.join(&expression.span),
+                    "it is returned",
+                ),
+                None => (
+                    DumbBrainType::Void,
+                    return_keyword.span.clone(),
+                    "'return' has no value",
+                ),
+            };
+            self.require_type(return_type, kind, &span, || reason.to_string());
+            if let (Some(inference), Some(_)) = (&mut self.inference, &expression) {
+                inference.returns_value = true;
+            }
+        }
+        let expression = match (expression, &self.function) {
+            (Some(expression), Some(function)) => {
+                Some(implicit_conversion(expression, function.return_type))
+            }
+            (expression, _) => expression,
+        };
+        let function = match &self.function {
+            Some(function) => function,
+            None => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::InvalidReturn,
+                    return_keyword.span.clone(),
+                    "'return' is not inside a function".to_string(),
+                ));
+                return BoundStatementNode::Return { expression };
+            }
+        };
+
+        match &expression {
+            None if function.return_type != DumbBrainType::Void
+                && function.return_type != DumbBrainType::Error =>
+            {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::InvalidReturn,
+                    return_keyword.span.clone(),
+                    format!(
+                        "function '{}' must return a value of type {}",
+                        function.name, function.return_type
+                    ),
+                ));
+            }
+            Some(expression) if function.return_type == DumbBrainType::Void => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::InvalidReturn,
+                    expression.span.clone(),
+                    format!("function '{}' does not return a value", function.name),
+                ));
+            }
+            Some(expression)
+                if expression.kind != function.return_type
+                    && expression.kind != DumbBrainType::Error
+                    && function.return_type != DumbBrainType::Error =>
+            {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::TypeMismatch,
+                    expression.span.clone(),
+                    format!(
+                        "expected return value of type {}, found {}",
+                        function.return_type, expression.kind
+                    ),
+                ));
+            }
+            _ => {}
+        }
+        BoundStatementNode::Return { expression }
+    }
+
+    fn bind_variable_declaration(
+        &mut self,
+        keyword_token: &Token,
+        identifier_token: &Token,
+        type_clause: Option<&TypeClauseSyntax>,
+        initializer: &ExpressionSyntax,
+    ) -> BoundStatementNode {
+        if let ExpressionSyntax::Lambda {
+            parameters, body, ..
+        } = initializer
+        {
+            return self.bind_lambda_declaration(
+                keyword_token,
+                identifier_token,
+                type_clause,
+                parameters,
+                body,
+            );
+        }
+        let mut initializer = self.bind_expression(initializer);
+        let mut kind = initializer.kind;
+        if kind == DumbBrainType::Void {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::TypeMismatch,
+                initializer.span.clone(),
+                "expression does not have a value".to_string(),
+            ));
+            kind = DumbBrainType::Error;
+        }
+        if let Some(type_clause) = type_clause {
+            let declared_kind = self.bind_type_clause(type_clause);
+            self.require_type(initializer.kind, declared_kind, &initializer.span, || {
+                format!(
+                    "'{}' is declared as {}",
+                    identifier_token.text, declared_kind
+                )
+            });
+            initializer = implicit_conversion(initializer, declared_kind);
+            if initializer.kind != declared_kind
+                && kind != DumbBrainType::Error
+                && declared_kind != DumbBrainType::Error
+            {
+                let mut diagnostic = Diagnostic::error(
+                    DiagnosticCode::CannotConvert,
+                    initializer.span.clone(),
+                    format!("cannot convert {} to {}", initializer.kind, declared_kind),
+                );
+                if Conversion::classify(initializer.kind, declared_kind) == Conversion::Explicit {
+                    diagnostic = diagnostic
+                        .with_note(format!("use {}(...) to convert explicitly", declared_kind));
+                }
+                self.diagnostics.report(diagnostic);
+            }
+            kind = declared_kind;
+        }
+        let read_only = keyword_token.kind == SyntaxKind::LetKeyword;
+        // Only a read-only variable keeps the value it was initialized with.
+        let constant = initializer.constant.clone().filter(|_| read_only);
+        let variable = self.declare_variable(identifier_token, read_only, kind, constant);
+        BoundStatementNode::VariableDeclaration {
+            variable,
+            initializer,
+        }
+    }
+
+    /// Declares the lambda a `let` declaration initializes as a function,
+    /// after inferring the types its parameters and result leave out. The
+    /// body becomes a function of the program, so nothing is left to run
+    /// where the lambda is declared.
+    ///
+    /// A lambda is not a value of its own. Its name can only be called, like
+    /// that of any other function, so it cannot be passed, returned or
+    /// assigned. It is called with a frame of its own, so it cannot use the
+    /// locals of the function around it either. Each parameter has a single
+    /// type rather than a generic one, so a parameter that nothing in the
+    /// body constrains, as in `(x) => x`, is an error.
+    fn bind_lambda_declaration(
+        &mut self,
+        keyword_token: &Token,
+        identifier_token: &Token,
+        type_clause: Option<&TypeClauseSyntax>,
+        parameters: &SeparatedSyntaxList<ParameterSyntax>,
+        body: &StatementSyntax,
+    ) -> BoundStatementNode {
+        if keyword_token.kind != SyntaxKind::LetKeyword || type_clause.is_some() {
+            let span = match type_clause {
+                Some(type_clause) => type_clause.span(),
+                None => keyword_token.span.clone(),
+            };
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::MisplacedLambda,
+                span,
+                String::from("a lambda can only initialize a 'let' declaration without a type"),
+            ));
+        }
+
+        let function = self.infer_lambda_signature(identifier_token, parameters, body);
+        // A missing name has already been reported by the parser.
+        if !identifier_token.text.is_empty() && !self.scope.try_declare_function(function.clone()) {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::AlreadyDeclared,
+                identifier_token.span.clone(),
+                format!("function '{}' is already declared", identifier_token.text),
+            ));
+        }
+
+        let outer_locals = self
+            .scope
+            .visible_variables()
+            .into_iter()
+            .filter(|variable| variable.local)
+            .cloned()
+            .collect();
+        let outer_locals = std::mem::replace(&mut self.outer_locals, outer_locals);
+        // The types of this lambda are known now, even if those of a lambda
+        // around it are still being inferred.
+        let outer_inference = self.inference.take();
+        let mut bound_body = self.bind_function_body(&function, parameters, body);
+        self.inference = outer_inference;
+        self.outer_locals = outer_locals;
+        // `(x) => e` returns the value of `e`.
+        if let BoundStatementNode::Expression { expression } = bound_body.node {
+            bound_body.node = match function.return_type {
+                DumbBrainType::Void => BoundStatementNode::Expression { expression },
+                kind => BoundStatementNode::Return {
+                    expression: Some(implicit_conversion(expression, kind)),
+                },
+            };
+        }
+        let bound_body = Lowerer::lower(bound_body);
+        self.check_control_flow(&bound_body, Some((&function, identifier_token)));
+        self.lambdas.insert(function, bound_body);
+        BoundStatementNode::Block { statements: vec![] }
+    }
+
+    /// Infers the types that the parameters and result of a lambda leave
+    /// out, by binding its body with a type variable for each of them. The
+    /// checks that bind the body are what constrain the variables. Only the
+    /// diagnostics about the inference are kept, since the body is bound
+    /// again once its types are known.
+    fn infer_lambda_signature(
+        &mut self,
+        identifier_token: &Token,
+        parameters: &SeparatedSyntaxList<ParameterSyntax>,
+        body: &StatementSyntax,
+    ) -> FunctionSymbol {
+        let mut inference = LambdaInference::default();
+        let name = &identifier_token.text;
+        let parameter_symbols = parameters
+            .iter()
+            .map(|parameter| {
+                let identifier_token = &parameter.identifier_token;
+                let kind = match &parameter.type_clause {
+                    Some(type_clause) => self.bind_type_clause(type_clause),
+                    None => inference
+                        .unifier
+                        .fresh(format!("parameter '{}'", identifier_token.text)),
+                };
+                VariableSymbol::new(
+                    identifier_token.text.clone(),
+                    true,
+                    true,
+                    kind,
+                    Some(identifier_token.span.clone()),
+                )
+            })
+            .collect();
+        let result = inference.unifier.fresh(format!("the result of '{}'", name));
+        let function = FunctionSymbol::new(name.clone(), parameter_symbols, result);
+
+        let outer_inference = self.inference.replace(inference);
+        let diagnostics = std::mem::take(&mut self.diagnostics);
+        let lambdas = std::mem::take(&mut self.lambdas);
+        // Recursive calls see the type variables too.
+        self.push_scope();
+        self.scope.try_declare_function(function.clone());
+        let bound_body = self.bind_function_body(&function, parameters, body);
+        self.pop_scope();
+        // `(x) => e` returns the value of `e`.
+        if let BoundStatementNode::Expression { expression } = &bound_body.node {
+            self.require_type(result, expression.kind, &expression.span, || {
+                String::from("it is the value of the lambda")
+            });
+            if let Some(inference) = &mut self.inference {
+                inference.returns_value = true;
+            }
+        }
+        self.diagnostics = diagnostics;
+        self.lambdas = lambdas;
+        let mut inference = std::mem::replace(&mut self.inference, outer_inference)
+            .expect("the inference was started above");
+
+        let mut parameter_symbols = function.parameters;
+        for symbol in &mut parameter_symbols {
+            symbol.kind = match inference.unifier.resolve(symbol.kind) {
+                DumbBrainType::Variable(_) => {
+                    inference.diagnostics.push(
+                        Diagnostic::error(
+                            DiagnosticCode::CannotInfer,
+                            symbol.declaration.clone().expect("parameters are declared"),
+                            format!("cannot infer the type of parameter '{}'", symbol.name),
+                        )
+                        .with_note(format!("give it a type, as in '{}: int'", symbol.name)),
+                    );
+                    DumbBrainType::Error
+                }
+                kind => kind,
+            };
+        }
+        // A result that nothing constrains either comes from a body that
+        // never returns a value, or from a parameter that was just reported.
+        let return_type = match inference.unifier.resolve(result) {
+            DumbBrainType::Variable(_) if !inference.returns_value => DumbBrainType::Void,
+            DumbBrainType::Variable(_) => DumbBrainType::Error,
+            kind => kind,
+        };
+        for diagnostic in inference.diagnostics {
+            self.diagnostics.report(diagnostic);
+        }
+        FunctionSymbol::new(name.clone(), parameter_symbols, return_type)
+    }
+
+    /// Reports a use of `variable` from a lambda that it is not visible to
+    /// when it runs. Constants are folded, so they can still be used.
+    fn check_capture(&mut self, variable: &VariableSymbol, identifier_token: &Token) {
+        if variable.constant.is_none() && self.outer_locals.contains(variable) {
+            let mut diagnostic = Diagnostic::error(
+                DiagnosticCode::CapturedLocal,
+                identifier_token.span.clone(),
+                format!(
+                    "a lambda cannot use '{}', which is local to the function around it",
+                    variable.name
+                ),
+            );
+            if let Some(declaration) = &variable.declaration {
+                diagnostic = diagnostic.with_label(
+                    declaration.clone(),
+                    format!("'{}' is declared here", variable.name),
+                );
+            }
+            self.diagnostics.report(diagnostic);
+        }
+    }
+
+    /// Declares a variable in the current scope, which is local if it is
+    /// inside a function body.
+    fn declare_variable(
+        &mut self,
+        identifier_token: &Token,
+        read_only: bool,
+        kind: DumbBrainType,
+        constant: Option<DumbBrainObject>,
+    ) -> VariableSymbol {
+        let mut variable = VariableSymbol::new(
+            identifier_token.text.clone(),
+            read_only,
+            self.function.is_some(),
+            kind,
+            Some(identifier_token.span.clone()),
+        );
+        variable.constant = constant;
+        // A missing name has already been reported by the parser.
+        if !identifier_token.text.is_empty() && !self.scope.try_declare(variable.clone()) {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::AlreadyDeclared,
+                identifier_token.span.clone(),
+                format!(
+                    "variable '{}' is already declared in this scope",
+                    identifier_token.text
+                ),
+            ));
+        }
+        variable
+    }
+
+    pub fn bind_expression(&mut self, expression: &ExpressionSyntax) -> BoundExpression {
         match expression {
             ExpressionSyntax::Literal { literal_token } => {
                 Self::bind_literal_expression(literal_token)
@@ -19,214 +796,1375 @@ impl Binder {
                 left,
                 operator_token,
                 right,
-            } => Self::bind_binary_expression(left, operator_token, right),
+            } => self.bind_binary_expression(left, operator_token, right),
             ExpressionSyntax::Unary {
                 operator_token,
                 right,
-            } => Self::bind_unary_expression(operator_token, right),
-            ExpressionSyntax::Parenthesized { expression, .. } => Self::bind_expression(expression),
-        }
-    }
-
-    fn resolve_binary_type(
-        left: &BoundExpression,
-        operator_token: &Token,
-        right: &BoundExpression,
-    ) -> DumbBrainType {
-        match operator_token.kind() {
-            SyntaxKind::PlusToken
-            | SyntaxKind::MinusToken
-            | SyntaxKind::StarToken
-            | SyntaxKind::SlashToken => {
-                if left.kind == DumbBrainType::Number && right.kind == DumbBrainType::Number {
-                    DumbBrainType::Number
-                } else {
-                    panic!(
-                        "unexpected types for {:?}: {:?}, {:?}",
-                        operator_token.kind(),
-                        left.kind,
-                        right.kind
-                    )
-                }
+            } => self.bind_unary_expression(operator_token, right),
+            ExpressionSyntax::Name { identifier_token } => {
+                self.bind_name_expression(identifier_token)
             }
-            SyntaxKind::EqualsEqualsToken
-            | SyntaxKind::BangEqualsToken
-            | SyntaxKind::LessToken
-            | SyntaxKind::LessEqualsToken
-            | SyntaxKind::GreaterToken
-            | SyntaxKind::GreaterEqualsToken
-            | SyntaxKind::AmpersandAmpersandToken
-            | SyntaxKind::PipePipeToken => DumbBrainType::Boolean,
-            _ => unreachable!(),
-        }
-    }
-
-    fn resolve_unary_type(operator_token: &Token, operand: &BoundExpression) -> DumbBrainType {
-        match operator_token.kind() {
-            SyntaxKind::PlusToken | SyntaxKind::MinusToken => {
-                if operand.kind == DumbBrainType::Number {
-                    DumbBrainType::Number
-                } else {
-                    todo!()
-                }
+            ExpressionSyntax::Assignment {
+                identifier_token,
+                expression,
+                ..
+            } => self.bind_assignment_expression(identifier_token, expression),
+            ExpressionSyntax::CompoundAssignment {
+                identifier_token,
+                operator_token,
+                expression,
+            } => self.bind_compound_assignment_expression(
+                identifier_token,
+                operator_token,
+                expression,
+            ),
+            ExpressionSyntax::PrefixIncrement {
+                operator_token,
+                identifier_token,
+            } => self.bind_increment_expression(identifier_token, operator_token, false),
+            ExpressionSyntax::PostfixIncrement {
+                identifier_token,
+                operator_token,
+            } => self.bind_increment_expression(identifier_token, operator_token, true),
+            ExpressionSyntax::Call {
+                identifier_token,
+                arguments,
+                ..
+            } => self.bind_call_expression(identifier_token, arguments, expression.span()),
+            ExpressionSyntax::Parenthesized { expression, .. } => self.bind_expression(expression),
+            ExpressionSyntax::Lambda { .. } => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::MisplacedLambda,
+                    expression.span(),
+                    String::from(
+                        "a lambda is not a value, and can only initialize a 'let' declaration",
+                    ),
+                ));
+                BoundExpression::new(
+                    BoundExpressionNode::Error,
+                    DumbBrainType::Error,
+                    expression.span(),
+                )
             }
-            _ => unreachable!(),
+            // The parser has already reported why this expression is broken.
+            ExpressionSyntax::Error { span, .. } => BoundExpression::new(
+                BoundExpressionNode::Error,
+                DumbBrainType::Error,
+                span.clone(),
+            ),
         }
     }
 
     fn bind_literal_expression(literal_token: &Token) -> BoundExpression {
         match literal_token.kind() {
-            SyntaxKind::NumberToken => BoundExpression {
-                node: BoundExpressionNode::Literal {
+            // The lexer has already reported why the literal has no value.
+            _ if literal_token.value.is_none() => BoundExpression::new(
+                BoundExpressionNode::Error,
+                DumbBrainType::Error,
+                literal_token.span.clone(),
+            ),
+            // The lexer has decided between int and float.
+            _ => BoundExpression::new(
+                BoundExpressionNode::Literal {
                     value: literal_token.value.clone(),
                 },
-                kind: DumbBrainType::Number,
+                literal_token.value.as_ref().unwrap().kind(),
+                literal_token.span.clone(),
+            ),
+        }
+    }
+
+    fn bind_name_expression(&mut self, identifier_token: &Token) -> BoundExpression {
+        match self.scope.lookup(&identifier_token.text).cloned() {
+            Some(variable) => {
+                self.check_capture(&variable, identifier_token);
+                let kind = variable.kind;
+                BoundExpression::new(
+                    BoundExpressionNode::Variable { variable },
+                    kind,
+                    identifier_token.span.clone(),
+                )
+            }
+            None => {
+                let diagnostic = match self.scope.lookup_function(&identifier_token.text) {
+                    Some(function) => Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        identifier_token.span.clone(),
+                        format!(
+                            "'{}' is a function of type {}, and can only be called",
+                            function.name,
+                            function.type_name()
+                        ),
+                    ),
+                    None => Diagnostic::error(
+                        DiagnosticCode::UndefinedName,
+                        identifier_token.span.clone(),
+                        format!("undefined name '{}'", identifier_token.text),
+                    ),
+                };
+                self.diagnostics.report(diagnostic);
+                BoundExpression::new(
+                    BoundExpressionNode::Error,
+                    DumbBrainType::Error,
+                    identifier_token.span.clone(),
+                )
+            }
+        }
+    }
+
+    fn bind_call_expression(
+        &mut self,
+        identifier_token: &Token,
+        arguments: &SeparatedSyntaxList<ExpressionSyntax>,
+        span: Span,
+    ) -> BoundExpression {
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.bind_expression(argument))
+            .collect::<Vec<_>>();
+        // Type names are not reserved, and only act as keywords where they
+        // are called like functions.
+        if let Some(kind) = DumbBrainType::lookup(&identifier_token.text) {
+            return self.bind_explicit_conversion(kind, arguments, span);
+        }
+        let error = BoundExpression::new(
+            BoundExpressionNode::Error,
+            DumbBrainType::Error,
+            span.clone(),
+        );
+
+        let function = match self.scope.lookup_function(&identifier_token.text) {
+            Some(function) => function.clone(),
+            None => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::UndefinedName,
+                    identifier_token.span.clone(),
+                    format!("undefined function '{}'", identifier_token.text),
+                ));
+                return error;
+            }
+        };
+        if arguments.len() != function.parameters.len() {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::WrongArgumentCount,
+                span,
+                format!(
+                    "function '{}' takes {} arguments but was given {}",
+                    function.name,
+                    function.parameters.len(),
+                    arguments.len()
+                ),
+            ));
+            return error;
+        }
+        for (argument, parameter) in arguments.iter().zip(&function.parameters) {
+            self.require_type(argument.kind, parameter.kind, &argument.span, || {
+                format!(
+                    "it is passed to parameter '{}' of '{}'",
+                    parameter.name, function.name
+                )
+            });
+        }
+        let arguments = arguments
+            .into_iter()
+            .zip(&function.parameters)
+            .map(|(argument, parameter)| implicit_conversion(argument, parameter.kind))
+            .collect::<Vec<_>>();
+        for (argument, parameter) in arguments.iter().zip(&function.parameters) {
+            if argument.kind != parameter.kind
+                && argument.kind != DumbBrainType::Error
+                && parameter.kind != DumbBrainType::Error
+            {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::TypeMismatch,
+                    argument.span.clone(),
+                    format!(
+                        "expected argument '{}' of type {}, found {}",
+                        parameter.name, parameter.kind, argument.kind
+                    ),
+                ));
+            }
+        }
+
+        let kind = function.return_type;
+        BoundExpression::new(
+            BoundExpressionNode::Call {
+                function,
+                arguments,
             },
-            SyntaxKind::TrueKeyword | SyntaxKind::FalseKeyword => BoundExpression {
-                node: BoundExpressionNode::Literal {
-                    value: literal_token.value.clone(),
+            kind,
+            span,
+        )
+    }
+
+    /// Binds a call whose name is a type, like `int(x)`, as a conversion of
+    /// its argument to that type.
+    fn bind_explicit_conversion(
+        &mut self,
+        kind: DumbBrainType,
+        mut arguments: Vec<BoundExpression>,
+        span: Span,
+    ) -> BoundExpression {
+        let error = BoundExpression::new(
+            BoundExpressionNode::Error,
+            DumbBrainType::Error,
+            span.clone(),
+        );
+        if arguments.len() != 1 {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::WrongArgumentCount,
+                span,
+                format!(
+                    "conversion to {} takes 1 argument but was given {}",
+                    kind,
+                    arguments.len()
+                ),
+            ));
+            return error;
+        }
+        let expression = arguments.pop().unwrap();
+        if expression.kind == DumbBrainType::Error {
+            return error;
+        }
+        // A conversion does not decide the type of what it converts.
+        if let DumbBrainType::Variable(_) = self.resolve_type(expression.kind) {
+            return BoundExpression::new(BoundExpressionNode::Error, kind, span);
+        }
+        match Conversion::classify(expression.kind, kind) {
+            Conversion::Identity => expression,
+            Conversion::Implicit | Conversion::Explicit => BoundExpression::new(
+                BoundExpressionNode::Conversion {
+                    expression: Box::new(expression),
                 },
-                kind: DumbBrainType::Boolean,
-            },
-            _ => unreachable!(),
+                kind,
+                span,
+            ),
+            Conversion::None => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::CannotConvert,
+                    expression.span.clone(),
+                    format!("cannot convert {} to {}", expression.kind, kind),
+                ));
+                error
+            }
         }
     }
 
-    fn bind_binary_expression(
-        left: &ExpressionSyntax,
+    fn bind_assignment_expression(
+        &mut self,
+        identifier_token: &Token,
+        expression: &ExpressionSyntax,
+    ) -> BoundExpression {
+        let expression = self.bind_expression(expression);
+        let span = identifier_token.span.join(&expression.span);
+        match self.lookup_assigned_variable(identifier_token) {
+            Some(variable) => {
+                self.bind_assignment(identifier_token, variable, expression, span, false)
+            }
+            None => BoundExpression::new(BoundExpressionNode::Error, DumbBrainType::Error, span),
+        }
+    }
+
+    /// Binds `x op= e` as `x = x op e`.
+    fn bind_compound_assignment_expression(
+        &mut self,
+        identifier_token: &Token,
         operator_token: &Token,
-        right: &ExpressionSyntax,
+        expression: &ExpressionSyntax,
     ) -> BoundExpression {
-        let left = Self::bind_expression(left);
-        let right = Self::bind_expression(right);
-
-        let resolved_type = Self::resolve_binary_type(&left, operator_token, &right);
-        let left = Box::new(left);
-        let right = Box::new(right);
-        match operator_token.kind() {
-            SyntaxKind::PlusToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Add,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::MinusToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Subtract,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::StarToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Multiply,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::SlashToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Divide,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::EqualsEqualsToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Equality,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::BangEqualsToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Inequality,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::LessToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Less,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::LessEqualsToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::LessEquals,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::GreaterToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::Greater,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::GreaterEqualsToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::GreaterEquals,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::AmpersandAmpersandToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::LogicalAnd,
-                },
-                kind: resolved_type,
+        let right = self.bind_expression(expression);
+        let span = identifier_token.span.join(&right.span);
+        let variable = match self.lookup_assigned_variable(identifier_token) {
+            Some(variable) => variable,
+            None => {
+                return BoundExpression::new(BoundExpressionNode::Error, DumbBrainType::Error, span)
+            }
+        };
+        let operator_kind = operator_token
+            .kind()
+            .assignment_operator()
+            .expect("compound assignments are parsed from assignment operators");
+        let left = BoundExpression::new(
+            BoundExpressionNode::Variable {
+                variable: variable.clone(),
             },
-            SyntaxKind::PipePipeToken => BoundExpression {
-                node: BoundExpressionNode::Binary {
-                    left,
-                    right,
-                    operation: BinaryOperation::LogicalOr,
-                },
-                kind: resolved_type,
+            variable.kind,
+            identifier_token.span.clone(),
+        );
+        let value = self.bind_binary_operator(left, operator_token, operator_kind, right);
+        self.bind_assignment(identifier_token, variable, value, span, false)
+    }
+
+    /// Binds `++x` as `x = x + 1`, and `x++` the same way except that it
+    /// evaluates to the value `x` had before.
+    fn bind_increment_expression(
+        &mut self,
+        identifier_token: &Token,
+        operator_token: &Token,
+        postfix: bool,
+    ) -> BoundExpression {
+        let span = if postfix {
+            identifier_token.span.join(&operator_token.span)
+        } else {
+            operator_token.span.join(&identifier_token.span)
+        };
+        let error = BoundExpression::new(
+            BoundExpressionNode::Error,
+            DumbBrainType::Error,
+            span.clone(),
+        );
+        let variable = match self.lookup_assigned_variable(identifier_token) {
+            Some(variable) => variable,
+            None => return error,
+        };
+        // An increment of a variable whose type is not inferred yet adds the
+        // int 1, just like `x + 1` does.
+        let one = match self.resolve_type(variable.kind) {
+            DumbBrainType::Int | DumbBrainType::Variable(_) => DumbBrainObject::Int(1),
+            DumbBrainType::Float => DumbBrainObject::Float(1.0),
+            DumbBrainType::Error => return error,
+            kind => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::UndefinedUnaryOperator,
+                    operator_token.span.clone(),
+                    format!(
+                        "operator '{}' is not defined for type {}",
+                        operator_token.text, kind
+                    ),
+                ));
+                return error;
+            }
+        };
+        let operator_kind = operator_token
+            .kind()
+            .assignment_operator()
+            .expect("increments are parsed from increment operators");
+        let left = BoundExpression::new(
+            BoundExpressionNode::Variable {
+                variable: variable.clone(),
             },
-            _ => unreachable!(),
+            variable.kind,
+            identifier_token.span.clone(),
+        );
+        let one_kind = one.kind();
+        let right = BoundExpression::new(
+            BoundExpressionNode::Literal { value: Some(one) },
+            one_kind,
+            operator_token.span.clone(),
+        );
+        let value = self.bind_binary_operator(left, operator_token, operator_kind, right);
+        self.bind_assignment(identifier_token, variable, value, span, postfix)
+    }
+
+    /// Looks up the variable named by the target of an assignment.
+    fn lookup_assigned_variable(&mut self, identifier_token: &Token) -> Option<VariableSymbol> {
+        let variable = self.scope.lookup(&identifier_token.text).cloned();
+        match &variable {
+            Some(variable) => self.check_capture(variable, identifier_token),
+            None => self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::UndefinedName,
+                identifier_token.span.clone(),
+                format!("undefined name '{}'", identifier_token.text),
+            )),
+        }
+        variable
+    }
+
+    /// Assigns `expression` to `variable`, reporting it if the variable is
+    /// read-only or of another type. A postfix assignment evaluates to the
+    /// value the variable had before.
+    fn bind_assignment(
+        &mut self,
+        identifier_token: &Token,
+        variable: VariableSymbol,
+        expression: BoundExpression,
+        span: Span,
+        postfix: bool,
+    ) -> BoundExpression {
+        self.require_type(variable.kind, expression.kind, &span, || {
+            format!("it is assigned to '{}'", variable.name)
+        });
+        let expression = implicit_conversion(expression, variable.kind);
+        if variable.read_only {
+            let mut diagnostic = Diagnostic::error(
+                DiagnosticCode::ReadOnlyAssignment,
+                identifier_token.span.clone(),
+                format!("cannot assign to read-only variable '{}'", variable.name),
+            );
+            if let Some(declaration) = &variable.declaration {
+                diagnostic = diagnostic.with_label(
+                    declaration.clone(),
+                    format!("'{}' is declared read-only here", variable.name),
+                );
+            }
+            self.diagnostics.report(diagnostic);
+        } else if expression.kind != variable.kind
+            && expression.kind != DumbBrainType::Error
+            && variable.kind != DumbBrainType::Error
+        {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::TypeMismatch,
+                expression.span.clone(),
+                format!(
+                    "cannot assign a value of type {} to variable '{}' of type {}",
+                    expression.kind, variable.name, variable.kind
+                ),
+            ));
+        }
+
+        let kind = variable.kind;
+        let expression = Box::new(expression);
+        let node = if postfix {
+            BoundExpressionNode::PostfixAssignment {
+                variable,
+                expression,
+            }
+        } else {
+            BoundExpressionNode::Assignment {
+                variable,
+                expression,
+            }
+        };
+        BoundExpression::new(node, kind, span)
+    }
+
+    fn bind_binary_expression(
+        &mut self,
+        left_syntax: &ExpressionSyntax,
+        operator_token: &Token,
+        right_syntax: &ExpressionSyntax,
+    ) -> BoundExpression {
+        let left = self.bind_expression(left_syntax);
+        let right = self.bind_expression(right_syntax);
+        // Rounding makes computed floats rarely exactly equal.
+        if matches!(
+            operator_token.kind(),
+            SyntaxKind::EqualsEqualsToken | SyntaxKind::BangEqualsToken
+        ) && left.kind.is_numeric()
+            && right.kind.is_numeric()
+            && (left.kind == DumbBrainType::Float || right.kind == DumbBrainType::Float)
+            && (is_computed(left_syntax) || is_computed(right_syntax))
+        {
+            self.diagnostics.report(
+                Diagnostic::warning(
+                    DiagnosticCode::FloatEquality,
+                    operator_token.span.clone(),
+                    format!("'{}' compares computed floats exactly", operator_token.text),
+                )
+                .with_note(String::from(
+                    "use approxEq(a, b, tolerance) to allow for rounding errors",
+                )),
+            );
+        }
+        self.bind_binary_operator(left, operator_token, operator_token.kind(), right)
+    }
+
+    /// Applies the binary operator `operator_kind`, written as
+    /// `operator_token`, to already bound operands.
+    fn bind_binary_operator(
+        &mut self,
+        mut left: BoundExpression,
+        operator_token: &Token,
+        operator_kind: SyntaxKind,
+        mut right: BoundExpression,
+    ) -> BoundExpression {
+        let span = left.span.join(&right.span);
+        let error = BoundExpression::new(
+            BoundExpressionNode::Error,
+            DumbBrainType::Error,
+            span.clone(),
+        );
+        let inferred_kinds = [left.kind, right.kind];
+        if let Some(kind) = self.infer_binary_operands(&mut left, operator_kind, &mut right, &span)
+        {
+            return BoundExpression::new(BoundExpressionNode::Error, kind, span);
+        }
+        if left.kind == DumbBrainType::Error || right.kind == DumbBrainType::Error {
+            return error;
+        }
+        // Mixed arithmetic and comparisons happen on floats.
+        let (left_kind, right_kind) = (left.kind, right.kind);
+        let (left, right) = if left_kind.is_numeric() && right_kind.is_numeric() {
+            (
+                implicit_conversion(left, right_kind),
+                implicit_conversion(right, left_kind),
+            )
+        } else {
+            (left, right)
+        };
+
+        match BoundBinaryOperator::bind(operator_kind, left.kind, right.kind) {
+            Some(operator) => {
+                let zero_divisor = match right.constant {
+                    Some(DumbBrainObject::Int(n)) => n == 0,
+                    Some(DumbBrainObject::Float(n)) => n == 0.0,
+                    _ => false,
+                };
+                let is_division = matches!(
+                    operator.operation,
+                    BinaryOperation::Divide
+                        | BinaryOperation::Modulo
+                        | BinaryOperation::FloorDivide
+                );
+                if is_division && zero_divisor {
+                    self.diagnostics.report(Diagnostic::warning(
+                        DiagnosticCode::DivisionByZero,
+                        span.clone(),
+                        String::from("division by zero"),
+                    ));
+                }
+                BoundExpression::new(
+                    BoundExpressionNode::Binary {
+                        left: Box::new(left),
+                        right: Box::new(right),
+                        operation: operator.operation,
+                    },
+                    operator.result_type,
+                    span,
+                )
+            }
+            None => {
+                let diagnostic = Diagnostic::error(
+                    DiagnosticCode::UndefinedBinaryOperator,
+                    operator_token.span.clone(),
+                    format!(
+                        "operator '{}' is not defined for types {} and {}",
+                        operator_token.text, left_kind, right_kind
+                    ),
+                );
+                self.report_operator_error(diagnostic, &inferred_kinds);
+                error
+            }
         }
     }
 
     fn bind_unary_expression(
+        &mut self,
         operator_token: &Token,
         operand: &ExpressionSyntax,
     ) -> BoundExpression {
-        let operand = Self::bind_expression(operand);
-        let resolved_type = Self::resolve_unary_type(operator_token, &operand);
-        let operand = Box::new(operand);
-        match operator_token.kind() {
-            SyntaxKind::PlusToken => BoundExpression {
-                node: BoundExpressionNode::Unary {
-                    operand,
-                    operation: UnaryOperation::Identity,
-                },
-                kind: resolved_type,
-            },
-            SyntaxKind::MinusToken => BoundExpression {
-                node: BoundExpressionNode::Unary {
-                    operand,
-                    operation: UnaryOperation::Negation,
+        let mut operand = self.bind_expression(operand);
+        let span = operator_token.span.join(&operand.span);
+        let error = BoundExpression::new(
+            BoundExpressionNode::Error,
+            DumbBrainType::Error,
+            span.clone(),
+        );
+        let inferred_kind = operand.kind;
+        if let Some(kind) = self.infer_unary_operand(operator_token.kind(), &mut operand, &span) {
+            return BoundExpression::new(BoundExpressionNode::Error, kind, span);
+        }
+        if operand.kind == DumbBrainType::Error {
+            return error;
+        }
+
+        match BoundUnaryOperator::bind(operator_token.kind(), operand.kind) {
+            Some(operator) => BoundExpression::new(
+                BoundExpressionNode::Unary {
+                    operand: Box::new(operand),
+                    operation: operator.operation,
                 },
-                kind: resolved_type,
-            },
-            _ => unreachable!(),
+                operator.result_type,
+                span,
+            ),
+            None => {
+                let diagnostic = Diagnostic::error(
+                    DiagnosticCode::UndefinedUnaryOperator,
+                    operator_token.span.clone(),
+                    format!(
+                        "operator '{}' is not defined for type {}",
+                        operator_token.text, operand.kind
+                    ),
+                );
+                self.report_operator_error(diagnostic, &[inferred_kind]);
+                error
+            }
+        }
+    }
+
+    /// The type `kind` stands for so far, while the types of a lambda are
+    /// being inferred.
+    fn resolve_type(&mut self, kind: DumbBrainType) -> DumbBrainType {
+        match &mut self.inference {
+            Some(inference) => inference.unifier.resolve(kind),
+            None => kind,
+        }
+    }
+
+    /// While the types of a lambda are being inferred, requires `a` and `b`
+    /// to be the same type because of `reason` at `span`. Known types are
+    /// left for the binder to check as usual.
+    fn require_type(
+        &mut self,
+        a: DumbBrainType,
+        b: DumbBrainType,
+        span: &Span,
+        reason: impl FnOnce() -> String,
+    ) {
+        let inference = match &mut self.inference {
+            Some(inference) => inference,
+            None => return,
+        };
+        if !matches!(a, DumbBrainType::Variable(_)) && !matches!(b, DumbBrainType::Variable(_)) {
+            return;
+        }
+        if let Err(diagnostic) = inference.unifier.unify(a, b, span, &reason()) {
+            inference.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Constrains the operands of a binary operator whose types are being
+    /// inferred by the overloads of `operator_kind`, and gives them the types
+    /// decided so far. If they are still unknown, returns the type of the
+    /// result, which either every overload agrees on, or is that of the
+    /// operands.
+    fn infer_binary_operands(
+        &mut self,
+        left: &mut BoundExpression,
+        operator_kind: SyntaxKind,
+        right: &mut BoundExpression,
+        span: &Span,
+    ) -> Option<DumbBrainType> {
+        self.inference.as_ref()?;
+        let text = operator_kind.text().expect("operators have fixed text");
+        match BoundBinaryOperator::common_operand_type(operator_kind) {
+            Some(kind) => {
+                for operand in [&*left, &*right].iter() {
+                    self.require_type(operand.kind, kind, span, || {
+                        format!("'{}' is only defined for {}", text, kind)
+                    });
+                }
+            }
+            None => self.require_type(left.kind, right.kind, span, || {
+                format!("both operands of '{}' have the same type", text)
+            }),
+        }
+        left.kind = self.resolve_type(left.kind);
+        right.kind = self.resolve_type(right.kind);
+        match (left.kind, right.kind) {
+            (DumbBrainType::Variable(_), _) | (_, DumbBrainType::Variable(_)) => {
+                Some(BoundBinaryOperator::common_result_type(operator_kind).unwrap_or(left.kind))
+            }
+            _ => None,
+        }
+    }
+
+    /// Like `infer_binary_operands`, for the operand of a unary operator.
+    /// Every unary operator results in the type of its operand.
+    fn infer_unary_operand(
+        &mut self,
+        operator_kind: SyntaxKind,
+        operand: &mut BoundExpression,
+        span: &Span,
+    ) -> Option<DumbBrainType> {
+        self.inference.as_ref()?;
+        if let Some(kind) = BoundUnaryOperator::common_operand_type(operator_kind) {
+            let text = operator_kind.text().expect("operators have fixed text");
+            self.require_type(operand.kind, kind, span, || {
+                format!("'{}' is only defined for {}", text, kind)
+            });
+        }
+        operand.kind = self.resolve_type(operand.kind);
+        match operand.kind {
+            DumbBrainType::Variable(_) => Some(operand.kind),
+            _ => None,
+        }
+    }
+
+    /// Reports an operator that is not defined for the types of its
+    /// operands. If any of them was inferred, the error is in the inference,
+    /// so it explains where their types came from, and the types become the
+    /// error type so that the body is not reported again once it is bound
+    /// with them.
+    fn report_operator_error(&mut self, diagnostic: Diagnostic, kinds: &[DumbBrainType]) {
+        let inference = match &mut self.inference {
+            Some(inference) => inference,
+            None => return self.diagnostics.report(diagnostic),
+        };
+        let mut inferred = kinds
+            .iter()
+            .copied()
+            .filter(|kind| matches!(kind, DumbBrainType::Variable(_)))
+            .collect::<Vec<_>>();
+        inferred.dedup_by_key(|kind| inference.unifier.representative(*kind));
+        let first = match inferred.first() {
+            Some(first) => *first,
+            None => return self.diagnostics.report(diagnostic),
+        };
+        let mut explained = Diagnostic::error(
+            DiagnosticCode::CannotInfer,
+            diagnostic.span.clone(),
+            format!(
+                "{}, as inferred for {}",
+                diagnostic.message,
+                inference.unifier.name(first)
+            ),
+        );
+        for kind in &inferred {
+            explained = inference.unifier.explain(explained, *kind);
+        }
+        for kind in &inferred {
+            inference.unifier.set_error(*kind, &diagnostic.span);
+        }
+        inference.diagnostics.push(explained);
+    }
+}
+
+/// Whether `expression` computes its value, rather than just naming it with
+/// a literal or a variable.
+fn is_computed(mut expression: &ExpressionSyntax) -> bool {
+    while let ExpressionSyntax::Parenthesized {
+        expression: inner, ..
+    } = expression
+    {
+        expression = inner;
+    }
+    !matches!(
+        expression,
+        ExpressionSyntax::Literal { .. } | ExpressionSyntax::Name { .. }
+    )
+}
+
+/// Converts `expression` to `kind` if that can happen implicitly, and
+/// otherwise leaves it for the caller to report the mismatch.
+fn implicit_conversion(expression: BoundExpression, kind: DumbBrainType) -> BoundExpression {
+    if Conversion::classify(expression.kind, kind) != Conversion::Implicit {
+        return expression;
+    }
+    let span = expression.span.clone();
+    BoundExpression::new(
+        BoundExpressionNode::Conversion {
+            expression: Box::new(expression),
+        },
+        kind,
+        span,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use dumbbrain_parser::Parser;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::*;
+
+    fn check(input: &str, expected: Expect) {
+        let tree = Parser::new(input).parse();
+        assert!(tree.diagnostics.is_empty(), "{:#?}", tree.diagnostics);
+        let mut binder = Binder::new();
+        let program = binder.bind_compilation_unit(&tree.root);
+        let mut output = vec![];
+        if let BoundStatementNode::Block { statements } = &program.statement.node {
+            if let Some(BoundStatement {
+                node: BoundStatementNode::Expression { expression },
+                ..
+            }) = statements.last()
+            {
+                output.push(expression.kind.to_string());
+            }
+        }
+        for diagnostic in binder.diagnostics() {
+            output.push(diagnostic.to_string());
         }
+        expected.assert_eq(&output.join("\n"));
+    }
+
+    #[test]
+    fn bind_logical_not() {
+        check("!true", expect!["bool"])
+    }
+
+    #[test]
+    fn bind_logical_or_and_xor() {
+        check("true || false ^^ true", expect!["bool"])
+    }
+
+    #[test]
+    fn logical_not_requires_boolean() {
+        check(
+            "!1",
+            expect![[r#"
+            ?
+            error[DB0003] at 1:1: operator '!' is not defined for type int"#]],
+        )
+    }
+
+    #[test]
+    fn logical_operators_require_booleans() {
+        check(
+            "1 || true",
+            expect![[r#"
+            ?
+            error[DB0004] at 1:3: operator '||' is not defined for types int and bool"#]],
+        )
+    }
+
+    #[test]
+    fn bind_string_concatenation() {
+        check(r#""a" + "b""#, expect!["string"])
+    }
+
+    #[test]
+    fn bind_string_comparison() {
+        check(r#""a" < "b""#, expect!["bool"])
+    }
+
+    #[test]
+    fn strings_only_support_plus() {
+        check(
+            r#""a" - "b""#,
+            expect![[r#"
+            ?
+            error[DB0004] at 1:5: operator '-' is not defined for types string and string"#]],
+        )
+    }
+
+    #[test]
+    fn strings_do_not_concatenate_with_numbers() {
+        check(
+            r#""a" + 1"#,
+            expect![[r#"
+            ?
+            error[DB0004] at 1:5: operator '+' is not defined for types string and int"#]],
+        )
+    }
+
+    #[test]
+    fn comparisons_require_matching_types() {
+        check(
+            "1 == true || \"a\" < 1",
+            expect![[r#"
+                ?
+                error[DB0004] at 1:3: operator '==' is not defined for types int and bool
+                error[DB0004] at 1:18: operator '<' is not defined for types string and int"#]],
+        )
+    }
+
+    #[test]
+    fn report_exact_comparisons_of_computed_floats() {
+        check(
+            "var a = 0.5\nvar b = a * 3.0\nb == 1.5\na * 3.0 == b\nb != (a / 2)\n0.1 + 0.2 == 0.3\na * 2 == 1",
+            expect![[r#"
+                bool
+                warning[DB0027] at 4:9: '==' compares computed floats exactly
+                  = note: use approxEq(a, b, tolerance) to allow for rounding errors
+                warning[DB0027] at 5:3: '!=' compares computed floats exactly
+                  = note: use approxEq(a, b, tolerance) to allow for rounding errors
+                warning[DB0027] at 6:11: '==' compares computed floats exactly
+                  = note: use approxEq(a, b, tolerance) to allow for rounding errors
+                warning[DB0027] at 7:7: '==' compares computed floats exactly
+                  = note: use approxEq(a, b, tolerance) to allow for rounding errors"#]],
+        )
+    }
+
+    #[test]
+    fn bitwise_operators_require_ints() {
+        check(
+            "var f = 1.0\nf & 1\n~true\n1 << 2.0\nfalse | true",
+            expect![[r#"
+                ?
+                error[DB0004] at 2:3: operator '&' is not defined for types float and int
+                error[DB0003] at 3:1: operator '~' is not defined for type bool
+                error[DB0004] at 4:3: operator '<<' is not defined for types int and float
+                error[DB0004] at 5:7: operator '|' is not defined for types bool and bool"#]],
+        )
+    }
+
+    #[test]
+    fn report_undefined_name() {
+        check(
+            "a + 1",
+            expect![[r#"
+            ?
+            error[DB0011] at 1:1: undefined name 'a'"#]],
+        )
+    }
+
+    #[test]
+    fn bind_variable_declarations() {
+        check("let a = 1\nvar b = a\n(b = 2) + a", expect!["int"])
+    }
+
+    #[test]
+    fn assignment_requires_declaration() {
+        check(
+            "a = 1",
+            expect![[r#"
+            ?
+            error[DB0011] at 1:1: undefined name 'a'"#]],
+        )
+    }
+
+    #[test]
+    fn assignment_keeps_variable_type() {
+        check(
+            "var a = 1\na = true",
+            expect![[r#"
+            int
+            error[DB0005] at 2:5: cannot assign a value of type bool to variable 'a' of type int"#]],
+        )
+    }
+
+    #[test]
+    fn cannot_assign_to_let() {
+        check(
+            "let a = 1\na = 2",
+            expect![[r#"
+            int
+            error[DB0013] at 2:1: cannot assign to read-only variable 'a'
+              1:5: 'a' is declared read-only here"#]],
+        )
+    }
+
+    #[test]
+    fn compound_assignments_keep_variable_type() {
+        check(
+            "var i = 1\nvar f = 1.5\nvar s = \"a\"\nf *= i\ns += \"b\"\ni /= f\ns -= \"b\"",
+            expect![[r#"
+                string
+                error[DB0005] at 6:1: cannot assign a value of type float to variable 'i' of type int
+                error[DB0004] at 7:3: operator '-=' is not defined for types string and string"#]],
+        )
+    }
+
+    #[test]
+    fn cannot_increment_let_or_non_numbers() {
+        check(
+            "let a = 1\nvar b = true\na += 2\n++a\nb--\nc++",
+            expect![[r#"
+                ?
+                error[DB0013] at 3:1: cannot assign to read-only variable 'a'
+                  1:5: 'a' is declared read-only here
+                error[DB0013] at 4:3: cannot assign to read-only variable 'a'
+                  1:5: 'a' is declared read-only here
+                error[DB0003] at 5:2: operator '--' is not defined for type bool
+                error[DB0011] at 6:1: undefined name 'c'"#]],
+        )
+    }
+
+    #[test]
+    fn cannot_assign_to_parameters_or_for_variables() {
+        check(
+            "function f(n: int) { n = 2 }\nfor i = 1 to 2 { i += 1 }",
+            expect![[r#"
+                error[DB0013] at 1:22: cannot assign to read-only variable 'n'
+                  1:12: 'n' is declared read-only here
+                error[DB0013] at 2:18: cannot assign to read-only variable 'i'
+                  2:5: 'i' is declared read-only here"#]],
+        )
+    }
+
+    #[test]
+    fn report_redeclaration() {
+        check(
+            "var a = 1\nlet a = true",
+            expect!["error[DB0012] at 2:5: variable 'a' is already declared in this scope"],
+        )
+    }
+
+    #[test]
+    fn blocks_shadow_outer_variables() {
+        check("let a = 1\n{ let a = \"a\"\na + a }\na", expect!["int"])
+    }
+
+    #[test]
+    fn block_variables_are_not_visible_outside() {
+        check(
+            "{ let a = 1 }\na",
+            expect![[r#"
+            ?
+            error[DB0011] at 2:1: undefined name 'a'"#]],
+        )
+    }
+
+    #[test]
+    fn conditions_must_be_boolean() {
+        check(
+            "if 1 { }\nwhile \"yes\" { }\nif 1 == 1 { } else { }",
+            expect![[r#"
+                error[DB0005] at 1:4: expected condition of type bool, found int
+                error[DB0005] at 2:7: expected condition of type bool, found string
+                warning[DB0023] at 3:4: condition is always true"#]],
+        )
+    }
+
+    #[test]
+    fn for_bounds_must_be_ints() {
+        check(
+            "for i = true to \"ten\" { }",
+            expect![[r#"
+                error[DB0005] at 1:9: expected lower bound of type int, found bool
+                error[DB0005] at 1:17: expected upper bound of type int, found string"#]],
+        )
+    }
+
+    #[test]
+    fn mixed_arithmetic_is_on_floats() {
+        check("var i = 1\nvar f = 2.5\ni / f", expect!["float"])
+    }
+
+    #[test]
+    fn floats_only_become_ints_explicitly() {
+        check(
+            "var f = 2.5\nvar i = 1\ni = f\ni = int(f)\nfloat(true)\nint(1, 2)\nnumber(i)",
+            expect![[r#"
+                float
+                error[DB0005] at 3:5: cannot assign a value of type float to variable 'i' of type int
+                error[DB0025] at 5:7: cannot convert bool to float
+                error[DB0016] at 6:1: conversion to int takes 1 argument but was given 2"#]],
+        )
+    }
+
+    #[test]
+    fn declarations_convert_to_their_annotation() {
+        check(
+            "let a: number = 1\nvar b: int = 2.5\nlet c: string = true\nlet d: text = 1\nvar e: bool = a > 0\ne",
+            expect![[r#"
+                bool
+                error[DB0025] at 2:14: cannot convert float to int
+                  = note: use int(...) to convert explicitly
+                error[DB0025] at 3:17: cannot convert bool to string
+                  = note: use string(...) to convert explicitly
+                error[DB0015] at 4:8: undefined type 'text'"#]],
+        )
+    }
+
+    #[test]
+    fn strings_convert_explicitly_to_and_from_values() {
+        check(
+            "var s = string(3) + string(2.5) + string(false)\nvar n = number(s)\nvar i = int(s)\nbool(s)\nbool(1)\nint(true)",
+            expect![[r#"
+                ?
+                error[DB0025] at 5:6: cannot convert int to bool
+                error[DB0025] at 6:5: cannot convert bool to int"#]],
+        )
+    }
+
+    #[test]
+    fn type_names_are_contextual_keywords() {
+        check(
+            "let string = \"1\"\nlet int: int = int(string)\nint",
+            expect!["int"],
+        )
+    }
+
+    #[test]
+    fn for_variable_is_read_only_and_scoped() {
+        check(
+            "for i = 1 to 10 i = 2\ni",
+            expect![[r#"
+            ?
+            error[DB0013] at 1:17: cannot assign to read-only variable 'i'
+              1:5: 'i' is declared read-only here
+            error[DB0011] at 2:1: undefined name 'i'"#]],
+        )
+    }
+
+    #[test]
+    fn break_and_continue_require_a_loop() {
+        check(
+            "break\nif true { continue }\nwhile true { if false break else continue }",
+            expect![[r#"
+                error[DB0014] at 1:1: 'break' is not inside a loop
+                error[DB0014] at 2:11: 'continue' is not inside a loop
+                warning[DB0021] at 3:23: unreachable code"#]],
+        )
+    }
+
+    #[test]
+    fn bind_function_call() {
+        check(
+            "function add(a: int, b: int): int { return a + b }\nadd(1, 2) > 2",
+            expect!["bool"],
+        )
+    }
+
+    #[test]
+    fn functions_can_be_called_before_declaration() {
+        check(
+            "even(10)\nfunction even(n: int): bool { if n == 0 return true\nreturn !even(n - 1) }",
+            expect!["bool"],
+        )
+    }
+
+    #[test]
+    fn void_calls_have_no_value() {
+        check(
+            "function log(s: string) { }\nlog(\"a\")\nlet x = log(\"b\")",
+            expect!["error[DB0005] at 3:9: expression does not have a value"],
+        )
+    }
+
+    #[test]
+    fn report_bad_calls() {
+        check(
+            "function f(a: int, b: bool) { }\nf(1)\nf(true, false)\ng()",
+            expect![[r#"
+                ?
+                error[DB0016] at 2:1: function 'f' takes 2 arguments but was given 1
+                error[DB0005] at 3:3: expected argument 'a' of type int, found bool
+                error[DB0011] at 4:1: undefined function 'g'"#]],
+        )
+    }
+
+    #[test]
+    fn report_bad_declarations() {
+        check(
+            "function f(a: int, a: integer) { }\nfunction f() { }",
+            expect![[r#"
+                error[DB0015] at 1:23: undefined type 'integer'
+                error[DB0012] at 2:10: function 'f' is already declared
+                error[DB0012] at 1:20: parameter 'a' is already declared"#]],
+        )
+    }
+
+    #[test]
+    fn report_bad_returns() {
+        check(
+            "return 1\nfunction f(): int { return }\nfunction g() { return 1 }\nfunction h(): string { return 1 }",
+            expect![[r#"
+                error[DB0017] at 1:1: 'return' is not inside a function
+                error[DB0017] at 2:21: function 'f' must return a value of type int
+                error[DB0017] at 3:23: function 'g' does not return a value
+                error[DB0005] at 4:31: expected return value of type string, found int"#]],
+        )
+    }
+
+    #[test]
+    fn function_locals_are_not_visible_outside() {
+        check(
+            "function f(a: int) { let b = a }\na + b",
+            expect![[r#"
+            ?
+            error[DB0011] at 2:1: undefined name 'a'
+            error[DB0011] at 2:5: undefined name 'b'"#]],
+        )
+    }
+
+    #[test]
+    fn scope_survives_binder() {
+        let mut binder = Binder::new();
+        binder.bind_compilation_unit(&Parser::new("var x = \"hi\"").parse().root);
+        let mut binder = Binder::with_scope(binder.into_scope());
+        binder.bind_compilation_unit(
+            &Parser::new("function twice(s: string): string { return s + s }")
+                .parse()
+                .root,
+        );
+        let mut binder = Binder::with_scope(binder.into_scope());
+        binder.bind_compilation_unit(&Parser::new("x = twice(x)").parse().root);
+        assert!(binder.diagnostics().is_empty());
+        let scope = binder.into_scope();
+        assert_eq!(scope.lookup("x").unwrap().kind, DumbBrainType::String);
+    }
+
+    #[test]
+    fn list_visible_variables() {
+        let mut binder = Binder::new();
+        binder.bind_compilation_unit(&Parser::new("var b = 1\nlet a = true").parse().root);
+        let mut binder = Binder::with_scope(BoundScope::with_parent(binder.into_scope()));
+        binder.bind_compilation_unit(&Parser::new("let b = \"b\"\nvar c = 0.5").parse().root);
+        let scope = binder.into_scope();
+        let variables = scope
+            .visible_variables()
+            .iter()
+            .map(|variable| variable.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(variables, ["let a: bool", "let b: string", "var c: float"]);
+    }
+
+    fn check_lambda_types(input: &str, expected: &[&str]) {
+        let tree = Parser::new(input).parse();
+        assert!(tree.diagnostics.is_empty(), "{:#?}", tree.diagnostics);
+        let mut binder = Binder::new();
+        binder.bind_compilation_unit(&tree.root);
+        assert!(
+            binder.diagnostics().is_empty(),
+            "{:#?}",
+            binder.diagnostics()
+        );
+        let scope = binder.into_scope();
+        let types = ["f", "g", "h"]
+            .iter()
+            .filter_map(|name| scope.lookup_function(name))
+            .map(|function| function.type_name())
+            .collect::<Vec<_>>();
+        assert_eq!(types, expected);
+    }
+
+    #[test]
+    fn infer_lambda_types() {
+        check_lambda_types(
+            "let f = (x) => x + 1\nlet g = (a, b) => a < b * 2.0\nlet h = (s) => { error(s) }",
+            &["int -> int", "(float, float) -> bool", "string -> void"],
+        );
+        check_lambda_types(
+            "let f = (x, y: float) => approxEq(x, y, 0.1) ^^ !f(x, y)\nlet g = () => f(1.0, 2.0)",
+            &["(float, float) -> bool", "() -> bool"],
+        );
+    }
+
+    #[test]
+    fn lambda_locals_take_types_from_initializers_and_uses() {
+        check_lambda_types(
+            "let f = (x) => {\n  var y = x\n  var z = y\n  z = z << 1\n  return y\n}\nlet g = (n, s) => {\n  var total = 0.5\n  for i = 1 to n { total += i }\n  if s == \"\" return total\n  return 0.0\n}\nlet h = (a) => {\n  var b = a\n  b++\n  return b > 0\n}",
+            &["int -> int", "(int, string) -> float", "int -> bool"],
+        );
+    }
+
+    #[test]
+    fn lambdas_are_called_like_functions() {
+        check(
+            "let f = (x: int) => x > 1\nf(2) && f(3.5)\nerror(f)\nf + 1",
+            expect![[r#"
+                ?
+                error[DB0005] at 2:11: expected argument 'x' of type int, found float
+                error[DB0005] at 3:7: 'f' is a function of type int -> bool, and can only be called
+                error[DB0005] at 4:1: 'f' is a function of type int -> bool, and can only be called"#]],
+        )
+    }
+
+    #[test]
+    fn report_conflicting_constraints() {
+        check(
+            "let f = (x) => x + 1 == x + \"a\"\nlet g = (a, b) => {\n  var c = a\n  c = b\n  return !a && b > 1\n}",
+            expect![[r#"
+                error[DB0028] at 1:25: conflicting types for parameter 'x': int and string
+                  1:16: inferred as int because both operands of '+' have the same type
+                  1:25: inferred as string because both operands of '+' have the same type
+                error[DB0028] at 5:16: conflicting types for parameter 'b': bool and int
+                  5:10: inferred as bool because '!' is only defined for bool
+                  5:16: inferred as int because both operands of '>' have the same type
+                  4:3: parameter 'a' and parameter 'b' have the same type because it is assigned to 'c'"#]],
+        )
+    }
+
+    #[test]
+    fn report_operators_undefined_for_inferred_types() {
+        check(
+            "let bad = (x) => x + true\nlet worse = (a, b) => {\n  let c = !a\n  return -b + a\n}",
+            expect![[r#"
+                error[DB0028] at 1:20: operator '+' is not defined for types bool and bool, as inferred for parameter 'x'
+                  1:18: inferred as bool because both operands of '+' have the same type
+                error[DB0028] at 4:13: operator '+' is not defined for types bool and bool, as inferred for parameter 'b'
+                  3:11: inferred as bool because '!' is only defined for bool
+                  4:10: parameter 'b' and parameter 'a' have the same type because both operands of '+' have the same type"#]],
+        )
+    }
+
+    /// A parameter has one type, so one that nothing decides is not made
+    /// generic.
+    #[test]
+    fn report_uninferable_parameters() {
+        check(
+            "let f = (x, y) => y * 2\nlet g = (a) => { return a }\nlet h = (x) => x\nf(\"a\", 1)",
+            expect![[r#"
+                int
+                error[DB0028] at 1:10: cannot infer the type of parameter 'x'
+                  = note: give it a type, as in 'x: int'
+                error[DB0028] at 2:10: cannot infer the type of parameter 'a'
+                  = note: give it a type, as in 'a: int'
+                error[DB0028] at 3:10: cannot infer the type of parameter 'x'
+                  = note: give it a type, as in 'x: int'"#]],
+        )
+    }
+
+    #[test]
+    fn lambdas_cannot_use_locals_of_their_function() {
+        check(
+            "function f(n: int): int {\n  let k = 2\n  var m = n\n  let g = (x) => {\n    m += x\n    return x + k + n\n  }\n  return g(m)\n}",
+            expect![[r#"
+                error[DB0030] at 5:5: a lambda cannot use 'm', which is local to the function around it
+                  3:7: 'm' is declared here
+                error[DB0030] at 6:20: a lambda cannot use 'n', which is local to the function around it
+                  1:12: 'n' is declared here"#]],
+        )
+    }
+
+    #[test]
+    fn report_misplaced_lambdas() {
+        check(
+            "var f = (x: int) => x\nlet g: int = (x: int) => x\nlet h = 1 + (x: int) => x\nerror((s: string) => s)\nfunction k(): int { return () => 1 }\nvar m = 1\nm = (x: int) => x",
+            expect![[r#"
+                int
+                error[DB0029] at 1:1: a lambda can only initialize a 'let' declaration without a type
+                error[DB0029] at 2:6: a lambda can only initialize a 'let' declaration without a type
+                error[DB0029] at 3:13: a lambda is not a value, and can only initialize a 'let' declaration
+                error[DB0029] at 4:7: a lambda is not a value, and can only initialize a 'let' declaration
+                error[DB0029] at 5:28: a lambda is not a value, and can only initialize a 'let' declaration
+                error[DB0029] at 7:5: a lambda is not a value, and can only initialize a 'let' declaration"#]],
+        )
+    }
+
+    #[test]
+    fn errors_do_not_cascade() {
+        check(
+            "!(1 ^^ 2) && true",
+            expect![[r#"
+            ?
+            error[DB0004] at 1:5: operator '^^' is not defined for types int and int"#]],
+        )
+    }
+
+    #[test]
+    fn report_missing_returns() {
+        check(
+            "function f(n: int): int { if n > 0 return n }\nfunction g(n: int): int { if n > 0 return 1 else return 2 }\nfunction h(): int { }\nfunction k(n: int): int { while n > 0 return n }",
+            expect![[r#"
+                error[DB0018] at 1:10: not all code paths return a value
+                error[DB0018] at 3:10: not all code paths return a value
+                error[DB0018] at 4:10: not all code paths return a value"#]],
+        )
+    }
+
+    #[test]
+    fn report_unreachable_code() {
+        check(
+            "function f(n: int): int {\n  if n > 0 return 1 else return 2\n  var m = n\n  while m > 0 { m = m - 1 }\n}\nwhile true {\n  break\n  if false 1 else 2\n}\nfor i = 1 to 3 { continue }",
+            expect![[r#"
+                warning[DB0021] at 3:3: unreachable code
+                warning[DB0021] at 8:3: unreachable code"#]],
+        )
+    }
+
+    #[test]
+    fn returning_from_every_loop_iteration_is_reachable() {
+        check(
+            "function f(): int { for i = 1 to 3 { return i }\nreturn 0 }",
+            expect![[""]],
+        )
+    }
+
+    #[test]
+    fn report_constant_division_by_zero() {
+        check(
+            "var n = 0\n1 / n\n1 / (2 - 2)\n(-1) / -0",
+            expect![[r#"
+                int
+                warning[DB0022] at 3:1: division by zero
+                warning[DB0022] at 4:2: division by zero"#]],
+        )
+    }
+
+    #[test]
+    fn report_constant_conditions() {
+        check(
+            "let limit = 3\nif limit > 2 { }\nwhile !(1 < 2) { }\nif true { }\nlet debug = false\nif (debug) { }\nvar n = 1\nif n > 2 { }",
+            expect![[r#"
+                warning[DB0023] at 2:4: condition is always true
+                warning[DB0023] at 3:7: condition is always false"#]],
+        )
+    }
+
+    #[test]
+    fn constant_conditions_decide_reachability() {
+        check(
+            "function f(): int { while true { } }\nfunction g(): int { if 1 > 2 return 1 }",
+            expect![[r#"
+                warning[DB0023] at 2:24: condition is always false
+                error[DB0018] at 2:10: not all code paths return a value
+                warning[DB0021] at 2:30: unreachable code"#]],
+        )
     }
 }
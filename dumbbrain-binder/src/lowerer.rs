@@ -0,0 +1,436 @@
+use dumbbrain::object::DumbBrainObject;
+use dumbbrain::span::Span;
+use dumbbrain::types::DumbBrainType;
+
+use crate::bound_tree::*;
+use crate::symbols::VariableSymbol;
+
+/// Rewrites structured control flow into a flat block of statements that
+/// only jump to labels, so that backends do not need to know about `if`,
+/// loops, `break` or `continue`.
+///
+/// ```text
+/// while condition body        for i = lower to upper body
+///
+///     goto continue               var i = lower
+/// body:                           let upperBound = upper
+///     body                        goto check
+/// continue:                   body:
+///     goto body if condition      body
+/// break:                      continue:
+///                                 i = i + 1
+///                             check:
+///                                 goto body if i <= upperBound
+///                             break:
+/// ```
+#[derive(Debug, Default)]
+pub struct Lowerer {
+    label_count: usize,
+    /// The labels `break` and `continue` jump to, innermost loop last.
+    loops: Vec<(BoundLabel, BoundLabel)>,
+    statements: Vec<BoundStatement>,
+}
+
+impl Lowerer {
+    /// Lowers `statement` into a block without nested blocks, `If`, `While`,
+    /// `For`, `Break` or `Continue` statements.
+    pub fn lower(statement: BoundStatement) -> BoundStatement {
+        let span = statement.span.clone();
+        let mut lowerer = Self::default();
+        lowerer.lower_statement(statement);
+        BoundStatement {
+            node: BoundStatementNode::Block {
+                statements: lowerer.statements,
+            },
+            span,
+        }
+    }
+
+    fn generate_label(&mut self, role: &str) -> BoundLabel {
+        self.label_count += 1;
+        BoundLabel {
+            name: format!("{}{}", role, self.label_count),
+        }
+    }
+
+    fn emit(&mut self, node: BoundStatementNode, span: &Span) {
+        self.statements.push(BoundStatement {
+            node,
+            span: span.clone(),
+        });
+    }
+
+    fn lower_statement(&mut self, statement: BoundStatement) {
+        let span = statement.span;
+        match statement.node {
+            BoundStatementNode::Block { statements } => {
+                for statement in statements {
+                    self.lower_statement(statement);
+                }
+            }
+            BoundStatementNode::If {
+                condition,
+                then_statement,
+                else_statement: None,
+            } => {
+                let end_label = self.generate_label("end");
+                self.emit(
+                    BoundStatementNode::ConditionalGoto {
+                        label: end_label.clone(),
+                        condition,
+                        jump_if_true: false,
+                    },
+                    &span,
+                );
+                self.lower_statement(*then_statement);
+                self.emit(BoundStatementNode::Label { label: end_label }, &span);
+            }
+            BoundStatementNode::If {
+                condition,
+                then_statement,
+                else_statement: Some(else_statement),
+            } => {
+                let else_label = self.generate_label("else");
+                let end_label = self.generate_label("end");
+                self.emit(
+                    BoundStatementNode::ConditionalGoto {
+                        label: else_label.clone(),
+                        condition,
+                        jump_if_true: false,
+                    },
+                    &span,
+                );
+                self.lower_statement(*then_statement);
+                self.emit(
+                    BoundStatementNode::Goto {
+                        label: end_label.clone(),
+                    },
+                    &span,
+                );
+                self.emit(BoundStatementNode::Label { label: else_label }, &span);
+                self.lower_statement(*else_statement);
+                self.emit(BoundStatementNode::Label { label: end_label }, &span);
+            }
+            BoundStatementNode::While { condition, body } => {
+                let body_label = self.generate_label("body");
+                let continue_label = self.generate_label("continue");
+                let break_label = self.generate_label("break");
+                self.emit(
+                    BoundStatementNode::Goto {
+                        label: continue_label.clone(),
+                    },
+                    &span,
+                );
+                self.emit(
+                    BoundStatementNode::Label {
+                        label: body_label.clone(),
+                    },
+                    &span,
+                );
+                self.lower_loop_body(*body, &break_label, &continue_label);
+                self.emit(
+                    BoundStatementNode::Label {
+                        label: continue_label,
+                    },
+                    &span,
+                );
+                self.emit(
+                    BoundStatementNode::ConditionalGoto {
+                        label: body_label,
+                        condition,
+                        jump_if_true: true,
+                    },
+                    &span,
+                );
+                self.emit(BoundStatementNode::Label { label: break_label }, &span);
+            }
+            BoundStatementNode::For {
+                variable,
+                lower_bound,
+                upper_bound,
+                body,
+            } => self.lower_for_statement(variable, lower_bound, upper_bound, *body, &span),
+            BoundStatementNode::Break => match self.loops.last() {
+                Some((break_label, _)) => {
+                    let label = break_label.clone();
+                    self.emit(BoundStatementNode::Goto { label }, &span);
+                }
+                // Already reported by the binder.
+                None => self.emit(BoundStatementNode::Break, &span),
+            },
+            BoundStatementNode::Continue => match self.loops.last() {
+                Some((_, continue_label)) => {
+                    let label = continue_label.clone();
+                    self.emit(BoundStatementNode::Goto { label }, &span);
+                }
+                None => self.emit(BoundStatementNode::Continue, &span),
+            },
+            node => self.emit(node, &span),
+        }
+    }
+
+    fn lower_loop_body(
+        &mut self,
+        body: BoundStatement,
+        break_label: &BoundLabel,
+        continue_label: &BoundLabel,
+    ) {
+        self.loops
+            .push((break_label.clone(), continue_label.clone()));
+        self.lower_statement(body);
+        self.loops.pop();
+    }
+
+    fn lower_for_statement(
+        &mut self,
+        variable: VariableSymbol,
+        lower_bound: BoundExpression,
+        upper_bound: BoundExpression,
+        body: BoundStatement,
+        span: &Span,
+    ) {
+        // The upper bound is only evaluated once, before the first iteration.
+        let upper_variable = VariableSymbol::new(
+            String::from("upperBound"),
+            true,
+            variable.local,
+            DumbBrainType::Int,
+            None,
+        );
+        let body_label = self.generate_label("body");
+        let continue_label = self.generate_label("continue");
+        let check_label = self.generate_label("check");
+        let break_label = self.generate_label("break");
+
+        self.emit(
+            BoundStatementNode::VariableDeclaration {
+                variable: variable.clone(),
+                initializer: lower_bound,
+            },
+            span,
+        );
+        self.emit(
+            BoundStatementNode::VariableDeclaration {
+                variable: upper_variable.clone(),
+                initializer: upper_bound,
+            },
+            span,
+        );
+        self.emit(
+            BoundStatementNode::Goto {
+                label: check_label.clone(),
+            },
+            span,
+        );
+        self.emit(
+            BoundStatementNode::Label {
+                label: body_label.clone(),
+            },
+            span,
+        );
+        self.lower_loop_body(body, &break_label, &continue_label);
+        self.emit(
+            BoundStatementNode::Label {
+                label: continue_label,
+            },
+            span,
+        );
+        // Leaving before the increment keeps a loop up to `i64::MAX` from
+        // overflowing after its last iteration.
+        self.emit(
+            BoundStatementNode::ConditionalGoto {
+                label: break_label.clone(),
+                condition: binary(
+                    variable_expression(&variable, span),
+                    BinaryOperation::Equality,
+                    variable_expression(&upper_variable, span),
+                    DumbBrainType::Boolean,
+                ),
+                jump_if_true: true,
+            },
+            span,
+        );
+        let increment = binary(
+            variable_expression(&variable, span),
+            BinaryOperation::Add,
+            BoundExpression::new(
+                BoundExpressionNode::Literal {
+                    value: Some(DumbBrainObject::Int(1)),
+                },
+                DumbBrainType::Int,
+                span.clone(),
+            ),
+            DumbBrainType::Int,
+        );
+        self.emit(
+            BoundStatementNode::Expression {
+                expression: BoundExpression::new(
+                    BoundExpressionNode::Assignment {
+                        variable: variable.clone(),
+                        expression: Box::new(increment),
+                    },
+                    DumbBrainType::Int,
+                    span.clone(),
+                ),
+            },
+            span,
+        );
+        self.emit(BoundStatementNode::Label { label: check_label }, span);
+        self.emit(
+            BoundStatementNode::ConditionalGoto {
+                label: body_label,
+                condition: binary(
+                    variable_expression(&variable, span),
+                    BinaryOperation::LessEquals,
+                    variable_expression(&upper_variable, span),
+                    DumbBrainType::Boolean,
+                ),
+                jump_if_true: true,
+            },
+            span,
+        );
+        self.emit(BoundStatementNode::Label { label: break_label }, span);
+    }
+}
+
+fn variable_expression(variable: &VariableSymbol, span: &Span) -> BoundExpression {
+    BoundExpression::new(
+        BoundExpressionNode::Variable {
+            variable: variable.clone(),
+        },
+        variable.kind,
+        span.clone(),
+    )
+}
+
+fn binary(
+    left: BoundExpression,
+    operation: BinaryOperation,
+    right: BoundExpression,
+    kind: DumbBrainType,
+) -> BoundExpression {
+    let span = left.span.join(&right.span);
+    BoundExpression::new(
+        BoundExpressionNode::Binary {
+            left: Box::new(left),
+            right: Box::new(right),
+            operation,
+        },
+        kind,
+        span,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use dumbbrain_parser::Parser;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::Binder;
+
+    fn check(input: &str, expected: Expect) {
+        let tree = Parser::new(input).parse();
+        let mut binder = Binder::new();
+        let program = binder.bind_compilation_unit(&tree.root);
+        assert!(
+            binder.diagnostics().is_empty(),
+            "{:#?}",
+            binder.diagnostics()
+        );
+        expected.assert_eq(&program.statement.to_string());
+    }
+
+    #[test]
+    fn lower_if_else() {
+        check(
+            "var x = 1\nif x > 0 x = -x else { x = x * (x + 3) }",
+            expect![[r#"
+            {
+                var x = 1
+                goto else1 unless x > 0
+                x = -x
+                goto end2
+            else1:
+                x = x * (x + 3)
+            end2:
+            }
+        "#]],
+        )
+    }
+
+    #[test]
+    fn lower_while_with_break_and_continue() {
+        check(
+            "var i = 0\nwhile true {\n  i = i + 1\n  if i < 3 continue\n  break\n}",
+            expect![[r#"
+                {
+                    var i = 0
+                    goto continue2
+                body1:
+                    i = i + 1
+                    goto end4 unless i < 3
+                    goto continue2
+                end4:
+                    goto break3
+                continue2:
+                    goto body1 if true
+                break3:
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn lower_nested_for() {
+        check(
+            "var n = 0\nfor i = 1 to 3 for j = i to 3 { if j == 2 break\nn = n + 1 }",
+            expect![[r#"
+                {
+                    var n = 0
+                    let i = 1
+                    let upperBound = 3
+                    goto check3
+                body1:
+                    let j = i
+                    let upperBound = 3
+                    goto check7
+                body5:
+                    goto end9 unless j == 2
+                    goto break8
+                end9:
+                    n = n + 1
+                continue6:
+                    goto break8 if j == upperBound
+                    j = j + 1
+                check7:
+                    goto body5 if j <= upperBound
+                break8:
+                continue2:
+                    goto break4 if i == upperBound
+                    i = i + 1
+                check3:
+                    goto body1 if i <= upperBound
+                break4:
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn lower_function_body() {
+        let tree =
+            Parser::new("function f(a: int): int { if a > 1 return a - 1\nreturn a }").parse();
+        let program = Binder::new().bind_compilation_unit(&tree.root);
+        let body = program.functions.values().next().unwrap();
+        expect![[r#"
+            {
+                goto end1 unless a > 1
+                return a - 1
+            end1:
+                return a
+            }
+        "#]]
+        .assert_eq(&body.to_string());
+    }
+}
@@ -0,0 +1,541 @@
+use std::convert::TryFrom;
+
+use dumbbrain::object::DumbBrainObject;
+use dumbbrain::types::DumbBrainType;
+use dumbbrain_lexer::syntax::SyntaxKind;
+
+use crate::bound_tree::BinaryOperation;
+use crate::bound_tree::UnaryOperation;
+
+/// One overload of a binary operator: the operation performed when the
+/// operator token is applied to operands of the given types, and the type of
+/// its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundBinaryOperator {
+    pub syntax_kind: SyntaxKind,
+    pub operation: BinaryOperation,
+    pub left_type: DumbBrainType,
+    pub right_type: DumbBrainType,
+    pub result_type: DumbBrainType,
+}
+
+impl BoundBinaryOperator {
+    const fn new(
+        syntax_kind: SyntaxKind,
+        operation: BinaryOperation,
+        operand_type: DumbBrainType,
+        result_type: DumbBrainType,
+    ) -> Self {
+        Self {
+            syntax_kind,
+            operation,
+            left_type: operand_type,
+            right_type: operand_type,
+            result_type,
+        }
+    }
+
+    /// The overload of `syntax_kind` for operands of the given types, if
+    /// there is one.
+    pub fn bind(
+        syntax_kind: SyntaxKind,
+        left_type: DumbBrainType,
+        right_type: DumbBrainType,
+    ) -> Option<Self> {
+        BINARY_OPERATORS.iter().copied().find(|operator| {
+            operator.syntax_kind == syntax_kind
+                && operator.left_type == left_type
+                && operator.right_type == right_type
+        })
+    }
+
+    /// The type that every overload of `syntax_kind` takes its operands as,
+    /// if they agree, like `bool` for `&&`.
+    pub fn common_operand_type(syntax_kind: SyntaxKind) -> Option<DumbBrainType> {
+        common_type(Self::overloads(syntax_kind).flat_map(|operator| {
+            std::iter::once(operator.left_type).chain(std::iter::once(operator.right_type))
+        }))
+    }
+
+    /// The type that every overload of `syntax_kind` results in, if they
+    /// agree, like `bool` for `<`.
+    pub fn common_result_type(syntax_kind: SyntaxKind) -> Option<DumbBrainType> {
+        common_type(Self::overloads(syntax_kind).map(|operator| operator.result_type))
+    }
+
+    fn overloads(syntax_kind: SyntaxKind) -> impl Iterator<Item = Self> {
+        BINARY_OPERATORS
+            .iter()
+            .copied()
+            .filter(move |operator| operator.syntax_kind == syntax_kind)
+    }
+}
+
+const BINARY_OPERATORS: &[BoundBinaryOperator] = {
+    use BinaryOperation::*;
+    use DumbBrainType::Boolean;
+    use DumbBrainType::Float;
+    use DumbBrainType::Int;
+    use DumbBrainType::String;
+    use SyntaxKind::*;
+    &[
+        BoundBinaryOperator::new(PlusToken, Add, Int, Int),
+        BoundBinaryOperator::new(MinusToken, Subtract, Int, Int),
+        BoundBinaryOperator::new(StarToken, Multiply, Int, Int),
+        BoundBinaryOperator::new(SlashToken, Divide, Int, Int),
+        BoundBinaryOperator::new(PercentToken, Modulo, Int, Int),
+        BoundBinaryOperator::new(TildeSlashToken, FloorDivide, Int, Int),
+        BoundBinaryOperator::new(StarStarToken, Power, Int, Int),
+        BoundBinaryOperator::new(EqualsEqualsToken, Equality, Int, Boolean),
+        BoundBinaryOperator::new(BangEqualsToken, Inequality, Int, Boolean),
+        BoundBinaryOperator::new(LessToken, Less, Int, Boolean),
+        BoundBinaryOperator::new(LessEqualsToken, LessEquals, Int, Boolean),
+        BoundBinaryOperator::new(GreaterToken, Greater, Int, Boolean),
+        BoundBinaryOperator::new(GreaterEqualsToken, GreaterEquals, Int, Boolean),
+        BoundBinaryOperator::new(AmpersandToken, BitwiseAnd, Int, Int),
+        BoundBinaryOperator::new(PipeToken, BitwiseOr, Int, Int),
+        BoundBinaryOperator::new(HatToken, BitwiseXor, Int, Int),
+        BoundBinaryOperator::new(LessLessToken, ShiftLeft, Int, Int),
+        BoundBinaryOperator::new(GreaterGreaterToken, ShiftRight, Int, Int),
+        BoundBinaryOperator::new(PlusToken, Add, Float, Float),
+        BoundBinaryOperator::new(MinusToken, Subtract, Float, Float),
+        BoundBinaryOperator::new(StarToken, Multiply, Float, Float),
+        BoundBinaryOperator::new(SlashToken, Divide, Float, Float),
+        BoundBinaryOperator::new(PercentToken, Modulo, Float, Float),
+        BoundBinaryOperator::new(TildeSlashToken, FloorDivide, Float, Float),
+        BoundBinaryOperator::new(StarStarToken, Power, Float, Float),
+        BoundBinaryOperator::new(EqualsEqualsToken, Equality, Float, Boolean),
+        BoundBinaryOperator::new(BangEqualsToken, Inequality, Float, Boolean),
+        BoundBinaryOperator::new(LessToken, Less, Float, Boolean),
+        BoundBinaryOperator::new(LessEqualsToken, LessEquals, Float, Boolean),
+        BoundBinaryOperator::new(GreaterToken, Greater, Float, Boolean),
+        BoundBinaryOperator::new(GreaterEqualsToken, GreaterEquals, Float, Boolean),
+        BoundBinaryOperator::new(EqualsEqualsToken, Equality, Boolean, Boolean),
+        BoundBinaryOperator::new(BangEqualsToken, Inequality, Boolean, Boolean),
+        BoundBinaryOperator::new(AmpersandAmpersandToken, LogicalAnd, Boolean, Boolean),
+        BoundBinaryOperator::new(PipePipeToken, LogicalOr, Boolean, Boolean),
+        BoundBinaryOperator::new(HatHatToken, LogicalXor, Boolean, Boolean),
+        BoundBinaryOperator::new(PlusToken, Add, String, String),
+        BoundBinaryOperator::new(EqualsEqualsToken, Equality, String, Boolean),
+        BoundBinaryOperator::new(BangEqualsToken, Inequality, String, Boolean),
+        BoundBinaryOperator::new(LessToken, Less, String, Boolean),
+        BoundBinaryOperator::new(LessEqualsToken, LessEquals, String, Boolean),
+        BoundBinaryOperator::new(GreaterToken, Greater, String, Boolean),
+        BoundBinaryOperator::new(GreaterEqualsToken, GreaterEquals, String, Boolean),
+    ]
+};
+
+/// One overload of a unary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundUnaryOperator {
+    pub syntax_kind: SyntaxKind,
+    pub operation: UnaryOperation,
+    pub operand_type: DumbBrainType,
+    pub result_type: DumbBrainType,
+}
+
+impl BoundUnaryOperator {
+    const fn new(
+        syntax_kind: SyntaxKind,
+        operation: UnaryOperation,
+        operand_type: DumbBrainType,
+    ) -> Self {
+        Self {
+            syntax_kind,
+            operation,
+            operand_type,
+            result_type: operand_type,
+        }
+    }
+
+    /// The overload of `syntax_kind` for an operand of the given type, if
+    /// there is one.
+    pub fn bind(syntax_kind: SyntaxKind, operand_type: DumbBrainType) -> Option<Self> {
+        UNARY_OPERATORS.iter().copied().find(|operator| {
+            operator.syntax_kind == syntax_kind && operator.operand_type == operand_type
+        })
+    }
+
+    /// The type that every overload of `syntax_kind` takes its operand as,
+    /// if they agree, like `bool` for `!`.
+    pub fn common_operand_type(syntax_kind: SyntaxKind) -> Option<DumbBrainType> {
+        common_type(
+            UNARY_OPERATORS
+                .iter()
+                .filter(|operator| operator.syntax_kind == syntax_kind)
+                .map(|operator| operator.operand_type),
+        )
+    }
+}
+
+/// The type that all of `types` are, if there is one.
+fn common_type(mut types: impl Iterator<Item = DumbBrainType>) -> Option<DumbBrainType> {
+    let first = types.next()?;
+    types.all(|kind| kind == first).then_some(first)
+}
+
+const UNARY_OPERATORS: &[BoundUnaryOperator] = {
+    use DumbBrainType::Boolean;
+    use DumbBrainType::Float;
+    use DumbBrainType::Int;
+    use SyntaxKind::*;
+    use UnaryOperation::*;
+    &[
+        BoundUnaryOperator::new(PlusToken, Identity, Int),
+        BoundUnaryOperator::new(MinusToken, Negation, Int),
+        BoundUnaryOperator::new(TildeToken, BitwiseNot, Int),
+        BoundUnaryOperator::new(PlusToken, Identity, Float),
+        BoundUnaryOperator::new(MinusToken, Negation, Float),
+        BoundUnaryOperator::new(BangToken, LogicalNegation, Boolean),
+    ]
+};
+
+/// Why an operation has no result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationError {
+    /// The operation is not defined for the operands it was given.
+    TypeMismatch,
+    /// The result of an int operation does not fit in an int.
+    Overflow,
+    /// An int was divided by zero.
+    DivisionByZero,
+    /// An int was raised to a negative power, which is not an int.
+    NegativeExponent,
+    /// A string converted to another type does not hold a value of it.
+    InvalidFormat,
+}
+
+impl BinaryOperation {
+    /// The result of the operation on values of the types it was bound for.
+    /// `&&` and `||` evaluate both operands here; short-circuiting is up to
+    /// the caller.
+    pub fn apply(
+        self,
+        left: &DumbBrainObject,
+        right: &DumbBrainObject,
+    ) -> Result<DumbBrainObject, OperationError> {
+        match self {
+            Self::Add
+            | Self::Subtract
+            | Self::Multiply
+            | Self::Divide
+            | Self::Modulo
+            | Self::FloorDivide
+            | Self::Power => evaluate_arithmetic(left, right, self),
+            Self::Equality
+            | Self::Inequality
+            | Self::Less
+            | Self::LessEquals
+            | Self::Greater
+            | Self::GreaterEquals => evaluate_comparison(left, right, self),
+            Self::LogicalAnd | Self::LogicalOr | Self::LogicalXor => {
+                evaluate_boolean_operation(left, right, self)
+            }
+            Self::BitwiseAnd
+            | Self::BitwiseOr
+            | Self::BitwiseXor
+            | Self::ShiftLeft
+            | Self::ShiftRight => evaluate_bitwise_operation(left, right, self),
+        }
+    }
+}
+
+impl UnaryOperation {
+    /// The result of the operation on a value of the type it was bound for.
+    pub fn apply(self, operand: &DumbBrainObject) -> Result<DumbBrainObject, OperationError> {
+        match (self, operand) {
+            (Self::Identity, operand) => Ok(operand.clone()),
+            (Self::Negation, DumbBrainObject::Int(n)) => n
+                .checked_neg()
+                .map(DumbBrainObject::Int)
+                .ok_or(OperationError::Overflow),
+            (Self::Negation, DumbBrainObject::Float(n)) => Ok(DumbBrainObject::Float(-n)),
+            (Self::BitwiseNot, DumbBrainObject::Int(n)) => Ok(DumbBrainObject::Int(!n)),
+            (Self::LogicalNegation, DumbBrainObject::Boolean(b)) => {
+                Ok(DumbBrainObject::Boolean(!b))
+            }
+            _ => Err(OperationError::TypeMismatch),
+        }
+    }
+}
+
+/// Int arithmetic is checked, and int division rounds towards zero. Floor
+/// division rounds down, and modulo takes the sign of the divisor, so that
+/// `a == (a ~/ b) * b + a % b` for ints. Division, floor division and modulo
+/// of ints by zero fail, while for floats they give an infinity or NaN.
+fn evaluate_arithmetic(
+    left: &DumbBrainObject,
+    right: &DumbBrainObject,
+    operation: BinaryOperation,
+) -> Result<DumbBrainObject, OperationError> {
+    match (left, right) {
+        (DumbBrainObject::Int(n), DumbBrainObject::Int(m)) => {
+            let value = match operation {
+                BinaryOperation::Add => n.checked_add(*m),
+                BinaryOperation::Subtract => n.checked_sub(*m),
+                BinaryOperation::Multiply => n.checked_mul(*m),
+                BinaryOperation::Divide
+                | BinaryOperation::Modulo
+                | BinaryOperation::FloorDivide
+                    if *m == 0 =>
+                {
+                    return Err(OperationError::DivisionByZero)
+                }
+                BinaryOperation::Divide => n.checked_div(*m),
+                // `i64::MIN % -1` overflows in Rust, but is 0.
+                BinaryOperation::Modulo => Some(floor_remainder(n.wrapping_rem(*m), *m)),
+                BinaryOperation::FloorDivide => n.checked_div(*m).map(|quotient| {
+                    if n % m != 0 && (*n < 0) != (*m < 0) {
+                        quotient - 1
+                    } else {
+                        quotient
+                    }
+                }),
+                BinaryOperation::Power => return int_power(*n, *m).map(DumbBrainObject::Int),
+                _ => unreachable!(),
+            };
+            value
+                .map(DumbBrainObject::Int)
+                .ok_or(OperationError::Overflow)
+        }
+        (DumbBrainObject::Float(n), DumbBrainObject::Float(m)) => {
+            let value = match operation {
+                BinaryOperation::Add => n + m,
+                BinaryOperation::Subtract => n - m,
+                BinaryOperation::Multiply => n * m,
+                BinaryOperation::Divide => n / m,
+                BinaryOperation::Modulo => floor_remainder(n % m, *m),
+                BinaryOperation::FloorDivide => (n / m).floor(),
+                BinaryOperation::Power => n.powf(*m),
+                _ => unreachable!(),
+            };
+            Ok(DumbBrainObject::Float(value))
+        }
+        (DumbBrainObject::String(a), DumbBrainObject::String(b))
+            if operation == BinaryOperation::Add =>
+        {
+            Ok(DumbBrainObject::String(format!("{}{}", a, b)))
+        }
+        _ => Err(OperationError::TypeMismatch),
+    }
+}
+
+/// Turns the remainder of a division that rounds towards zero into that of
+/// one that rounds down, which has the sign of `divisor`.
+fn floor_remainder<T>(remainder: T, divisor: T) -> T
+where
+    T: Copy + Default + PartialOrd + std::ops::Add<Output = T>,
+{
+    let zero = T::default();
+    if remainder != zero && (remainder < zero) != (divisor < zero) {
+        remainder + divisor
+    } else {
+        remainder
+    }
+}
+
+/// Raises an int to an int power. A negative exponent would give a fraction,
+/// so it fails rather than rounding to an int.
+fn int_power(base: i64, exponent: i64) -> Result<i64, OperationError> {
+    if exponent < 0 {
+        return Err(OperationError::NegativeExponent);
+    }
+    match (base, u32::try_from(exponent)) {
+        (_, Ok(exponent)) => base.checked_pow(exponent).ok_or(OperationError::Overflow),
+        // Only these bases stay in range for exponents this large.
+        (0 | 1, Err(_)) => Ok(base),
+        (-1, Err(_)) if exponent % 2 == 0 => Ok(1),
+        (-1, Err(_)) => Ok(-1),
+        _ => Err(OperationError::Overflow),
+    }
+}
+
+/// Shifts never fail: bits shifted out are lost, shifting by 64 or more
+/// shifts every bit out, and shifting by a negative amount shifts the other
+/// way.
+fn evaluate_bitwise_operation(
+    left: &DumbBrainObject,
+    right: &DumbBrainObject,
+    operation: BinaryOperation,
+) -> Result<DumbBrainObject, OperationError> {
+    let (n, m) = match (left, right) {
+        (DumbBrainObject::Int(n), DumbBrainObject::Int(m)) => (*n, *m),
+        _ => return Err(OperationError::TypeMismatch),
+    };
+    let value = match operation {
+        BinaryOperation::BitwiseAnd => n & m,
+        BinaryOperation::BitwiseOr => n | m,
+        BinaryOperation::BitwiseXor => n ^ m,
+        BinaryOperation::ShiftLeft if m < 0 => shift_right(n, m.unsigned_abs()),
+        BinaryOperation::ShiftLeft => shift_left(n, m.unsigned_abs()),
+        BinaryOperation::ShiftRight if m < 0 => shift_left(n, m.unsigned_abs()),
+        BinaryOperation::ShiftRight => shift_right(n, m.unsigned_abs()),
+        _ => unreachable!(),
+    };
+    Ok(DumbBrainObject::Int(value))
+}
+
+fn shift_left(n: i64, amount: u64) -> i64 {
+    if amount >= 64 {
+        0
+    } else {
+        n << amount
+    }
+}
+
+fn shift_right(n: i64, amount: u64) -> i64 {
+    n >> amount.min(63)
+}
+
+fn evaluate_boolean_operation(
+    left: &DumbBrainObject,
+    right: &DumbBrainObject,
+    operation: BinaryOperation,
+) -> Result<DumbBrainObject, OperationError> {
+    let (b, c) = match (left, right) {
+        (DumbBrainObject::Boolean(b), DumbBrainObject::Boolean(c)) => (*b, *c),
+        _ => return Err(OperationError::TypeMismatch),
+    };
+    let value = match operation {
+        BinaryOperation::LogicalAnd => b && c,
+        BinaryOperation::LogicalOr => b || c,
+        BinaryOperation::LogicalXor => b != c,
+        _ => unreachable!(),
+    };
+    Ok(DumbBrainObject::Boolean(value))
+}
+
+fn evaluate_comparison(
+    left: &DumbBrainObject,
+    right: &DumbBrainObject,
+    operation: BinaryOperation,
+) -> Result<DumbBrainObject, OperationError> {
+    let value = match (left, right) {
+        (DumbBrainObject::Int(n), DumbBrainObject::Int(m)) => compare(n, m, operation),
+        // Floats compare exactly, so NaN is unequal to everything.
+        (DumbBrainObject::Float(n), DumbBrainObject::Float(m)) => compare(n, m, operation),
+        (DumbBrainObject::String(a), DumbBrainObject::String(b)) => compare(a, b, operation),
+        (DumbBrainObject::Boolean(b), DumbBrainObject::Boolean(c)) => match operation {
+            BinaryOperation::Equality => b == c,
+            BinaryOperation::Inequality => b != c,
+            _ => return Err(OperationError::TypeMismatch),
+        },
+        _ => return Err(OperationError::TypeMismatch),
+    };
+    Ok(DumbBrainObject::Boolean(value))
+}
+
+fn compare<T: PartialOrd>(a: T, b: T, operation: BinaryOperation) -> bool {
+    match operation {
+        BinaryOperation::Equality => a == b,
+        BinaryOperation::Inequality => a != b,
+        BinaryOperation::Less => a < b,
+        BinaryOperation::LessEquals => a <= b,
+        BinaryOperation::Greater => a > b,
+        BinaryOperation::GreaterEquals => a >= b,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn int_arithmetic_is_checked() {
+        use DumbBrainObject::Int;
+        let apply = |operation: BinaryOperation, n, m| operation.apply(&Int(n), &Int(m));
+        assert_eq!(apply(BinaryOperation::Divide, -7, 2), Ok(Int(-3)));
+        assert_eq!(
+            apply(BinaryOperation::Add, i64::MAX, 1),
+            Err(OperationError::Overflow)
+        );
+        assert_eq!(
+            apply(BinaryOperation::Divide, i64::MIN, -1),
+            Err(OperationError::Overflow)
+        );
+        assert_eq!(
+            apply(BinaryOperation::Divide, 1, 0),
+            Err(OperationError::DivisionByZero)
+        );
+        assert_eq!(
+            UnaryOperation::Negation.apply(&Int(i64::MIN)),
+            Err(OperationError::Overflow)
+        );
+    }
+
+    #[test]
+    fn int_modulo_rounds_like_floor_division() {
+        use DumbBrainObject::Int;
+        let apply = |operation: BinaryOperation, n, m| operation.apply(&Int(n), &Int(m));
+        for (n, m) in [(7, 2), (-7, 2), (7, -2), (-7, -2), (6, -3), (-7, 3)]
+            .iter()
+            .copied()
+        {
+            let floored = apply(BinaryOperation::FloorDivide, n, m).unwrap();
+            assert_eq!(floored, Int((n as f64 / m as f64).floor() as i64));
+            let remainder = apply(BinaryOperation::Modulo, n, m).unwrap();
+            match (floored, remainder) {
+                (Int(quotient), Int(remainder)) => {
+                    assert_eq!(quotient * m + remainder, n);
+                    assert!(remainder == 0 || (remainder < 0) == (m < 0));
+                }
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(apply(BinaryOperation::Modulo, -7, 3), Ok(Int(2)));
+        assert_eq!(apply(BinaryOperation::Modulo, 7, -3), Ok(Int(-2)));
+        assert_eq!(apply(BinaryOperation::Modulo, i64::MIN, -1), Ok(Int(0)));
+        assert_eq!(
+            apply(BinaryOperation::FloorDivide, 1, 0),
+            Err(OperationError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn shifts_by_any_amount() {
+        use DumbBrainObject::Int;
+        let apply = |operation: BinaryOperation, n, m| operation.apply(&Int(n), &Int(m));
+        assert_eq!(apply(BinaryOperation::ShiftLeft, 3, 2), Ok(Int(12)));
+        assert_eq!(apply(BinaryOperation::ShiftLeft, 3, -1), Ok(Int(1)));
+        assert_eq!(apply(BinaryOperation::ShiftLeft, 1, 63), Ok(Int(i64::MIN)));
+        assert_eq!(apply(BinaryOperation::ShiftLeft, 1, 64), Ok(Int(0)));
+        assert_eq!(apply(BinaryOperation::ShiftRight, -8, 1), Ok(Int(-4)));
+        assert_eq!(apply(BinaryOperation::ShiftRight, -8, 100), Ok(Int(-1)));
+        assert_eq!(apply(BinaryOperation::ShiftRight, 8, i64::MIN), Ok(Int(0)));
+    }
+
+    #[test]
+    fn int_powers() {
+        assert_eq!(int_power(-3, 3), Ok(-27));
+        assert_eq!(int_power(0, 0), Ok(1));
+        assert_eq!(int_power(2, -1), Err(OperationError::NegativeExponent));
+        assert_eq!(int_power(1, -3), Err(OperationError::NegativeExponent));
+        assert_eq!(int_power(0, -1), Err(OperationError::NegativeExponent));
+        assert_eq!(int_power(-1, i64::MAX), Ok(-1));
+        assert_eq!(int_power(2, 63), Err(OperationError::Overflow));
+        assert_eq!(int_power(2, i64::MAX), Err(OperationError::Overflow));
+    }
+
+    #[test]
+    fn operators_are_written_with_their_token() {
+        for operator in BINARY_OPERATORS {
+            assert_eq!(operator.operation.syntax_kind(), operator.syntax_kind);
+        }
+        for operator in UNARY_OPERATORS {
+            assert_eq!(operator.operation.syntax_kind(), operator.syntax_kind);
+        }
+    }
+
+    #[test]
+    fn overloads_are_unique() {
+        for (i, operator) in BINARY_OPERATORS.iter().enumerate() {
+            let found = BoundBinaryOperator::bind(
+                operator.syntax_kind,
+                operator.left_type,
+                operator.right_type,
+            );
+            assert_eq!(found, Some(BINARY_OPERATORS[i]));
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+use dumbbrain_lexer::syntax::SyntaxKind;
+
+use crate::bound_tree::*;
+use crate::symbols::FunctionSymbol;
+use crate::symbols::VariableSymbol;
+
+/// How tightly an expression binds, using the parser's precedences, so that
+/// operands are only parenthesized where the source would need it.
+fn precedence(expression: &BoundExpression) -> usize {
+    match &expression.node {
+        BoundExpressionNode::Assignment { .. } => 0,
+        BoundExpressionNode::Binary { operation, .. } => {
+            operation.syntax_kind().binary_precedence()
+        }
+        BoundExpressionNode::Unary { operation, .. } => operation.syntax_kind().unary_precedence(),
+        _ => usize::MAX,
+    }
+}
+
+fn write_operand(
+    f: &mut Formatter<'_>,
+    operand: &BoundExpression,
+    min_precedence: usize,
+) -> Result {
+    if precedence(operand) < min_precedence {
+        write!(f, "({})", operand)
+    } else {
+        write!(f, "{}", operand)
+    }
+}
+
+/// Whether `operand` starts with the same sign as `operation`, so that
+/// writing them together would read back as an increment or decrement, like
+/// `-(-x)` as `--x`.
+fn doubles_sign(operation: UnaryOperation, operand: &BoundExpression) -> bool {
+    match &operand.node {
+        BoundExpressionNode::Unary {
+            operation: inner, ..
+        } => {
+            *inner == operation
+                && matches!(
+                    operation,
+                    UnaryOperation::Identity | UnaryOperation::Negation
+                )
+        }
+        _ => false,
+    }
+}
+
+fn text(kind: SyntaxKind) -> &'static str {
+    kind.text().expect("operators have fixed text")
+}
+
+impl Display for BoundExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.node {
+            BoundExpressionNode::Literal { value: Some(value) } => write!(f, "{}", value),
+            BoundExpressionNode::Literal { value: None } | BoundExpressionNode::Error => {
+                write!(f, "?")
+            }
+            BoundExpressionNode::Binary {
+                left,
+                right,
+                operation,
+            } => {
+                // The operand on the side the operator does not associate to
+                // needs parentheses at the same precedence.
+                let precedence = precedence(self);
+                let (left_precedence, right_precedence) =
+                    if operation.syntax_kind().is_right_associative() {
+                        (precedence + 1, precedence)
+                    } else {
+                        (precedence, precedence + 1)
+                    };
+                write_operand(f, left, left_precedence)?;
+                write!(f, " {} ", operation)?;
+                write_operand(f, right, right_precedence)
+            }
+            BoundExpressionNode::Unary { operand, operation } => {
+                write!(f, "{}", operation)?;
+                if doubles_sign(*operation, operand) {
+                    write!(f, "({})", operand)
+                } else {
+                    write_operand(f, operand, precedence(self))
+                }
+            }
+            BoundExpressionNode::Variable { variable } => write!(f, "{}", variable.name),
+            BoundExpressionNode::Assignment {
+                variable,
+                expression,
+            } => write!(f, "{} = {}", variable.name, expression),
+            BoundExpressionNode::PostfixAssignment {
+                variable,
+                expression,
+            } => match &expression.node {
+                // `x++` binds to `x + 1`, and `x--` to `x - 1`.
+                BoundExpressionNode::Binary { operation, .. } => {
+                    write!(f, "{}{}{}", variable.name, operation, operation)
+                }
+                // The increment was folded, which only happens to read-only
+                // variables and so after an error.
+                _ => write!(f, "?"),
+            },
+            BoundExpressionNode::Call {
+                function,
+                arguments,
+            } => {
+                write!(f, "{}(", function.name)?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", argument)?;
+                }
+                write!(f, ")")
+            }
+            BoundExpressionNode::Conversion { expression } => {
+                write!(f, "{}({})", self.kind, expression)
+            }
+        }
+    }
+}
+
+impl Display for BinaryOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", text(self.syntax_kind()))
+    }
+}
+
+impl Display for UnaryOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", text(self.syntax_kind()))
+    }
+}
+
+/// Writes `statement` on its own lines, indented by `indent` levels. Labels
+/// are written one level further out than the statements around them, so
+/// that the jump targets of a lowered block stand out.
+fn write_statement(f: &mut Formatter<'_>, statement: &BoundStatement, indent: usize) -> Result {
+    let pad = "    ".repeat(indent);
+    match &statement.node {
+        BoundStatementNode::Block { statements } => {
+            writeln!(f, "{}{{", pad)?;
+            for statement in statements {
+                write_statement(f, statement, indent + 1)?;
+            }
+            writeln!(f, "{}}}", pad)
+        }
+        BoundStatementNode::Expression { expression } => writeln!(f, "{}{}", pad, expression),
+        BoundStatementNode::VariableDeclaration {
+            variable,
+            initializer,
+        } => {
+            let keyword = if variable.read_only { "let" } else { "var" };
+            writeln!(f, "{}{} {} = {}", pad, keyword, variable.name, initializer)
+        }
+        BoundStatementNode::If {
+            condition,
+            then_statement,
+            else_statement,
+        } => {
+            writeln!(f, "{}if {}", pad, condition)?;
+            write_statement(f, then_statement, indent + 1)?;
+            if let Some(else_statement) = else_statement {
+                writeln!(f, "{}else", pad)?;
+                write_statement(f, else_statement, indent + 1)?;
+            }
+            Ok(())
+        }
+        BoundStatementNode::While { condition, body } => {
+            writeln!(f, "{}while {}", pad, condition)?;
+            write_statement(f, body, indent + 1)
+        }
+        BoundStatementNode::For {
+            variable,
+            lower_bound,
+            upper_bound,
+            body,
+        } => {
+            writeln!(
+                f,
+                "{}for {} = {} to {}",
+                pad, variable.name, lower_bound, upper_bound
+            )?;
+            write_statement(f, body, indent + 1)
+        }
+        BoundStatementNode::Break => writeln!(f, "{}break", pad),
+        BoundStatementNode::Continue => writeln!(f, "{}continue", pad),
+        BoundStatementNode::Return { expression: None } => writeln!(f, "{}return", pad),
+        BoundStatementNode::Return {
+            expression: Some(expression),
+        } => writeln!(f, "{}return {}", pad, expression),
+        BoundStatementNode::Label { label } => {
+            writeln!(
+                f,
+                "{}{}:",
+                "    ".repeat(indent.saturating_sub(1)),
+                label.name
+            )
+        }
+        BoundStatementNode::Goto { label } => writeln!(f, "{}goto {}", pad, label.name),
+        BoundStatementNode::ConditionalGoto {
+            label,
+            condition,
+            jump_if_true,
+        } => {
+            let keyword = if *jump_if_true { "if" } else { "unless" };
+            writeln!(f, "{}goto {} {} {}", pad, label.name, keyword, condition)
+        }
+    }
+}
+
+impl Display for BoundStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write_statement(f, self, 0)
+    }
+}
+
+impl Display for VariableSymbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let keyword = if self.read_only { "let" } else { "var" };
+        write!(f, "{} {}: {}", keyword, self.name, self.kind)
+    }
+}
+
+impl Display for FunctionSymbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "function {}(", self.name)?;
+        for (i, parameter) in self.parameters.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", parameter.name, parameter.kind)?;
+        }
+        write!(f, "): {}", self.return_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dumbbrain_parser::Parser;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::Binder;
+
+    fn check(input: &str, expected: Expect) {
+        let tree = Parser::new(input).parse();
+        let program = Binder::new().bind_compilation_unit(&tree.root);
+        let mut output = String::new();
+        for (function, body) in &program.functions {
+            output += &format!("{}\n{}", function, body);
+        }
+        output += &program.statement.to_string();
+        expected.assert_eq(&output);
+    }
+
+    #[test]
+    fn print_parentheses_only_where_needed() {
+        check(
+            "var a = 1\nvar s = \"a\\n\"\n(a - (a - 3)) * -(a + 5) == a - 2 - a && !(a > 0 || s < \"b\")",
+            expect![[r#"
+                {
+                    var a = 1
+                    var s = "a\n"
+                    (a - (a - 3)) * -(a + 5) == a - 2 - a && !(a > 0 || s < "b")
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn print_nested_assignments_and_calls() {
+        check(
+            "function f(a: int, b: int): int { return a }\nvar x = 1\nvar y = 2\nx = y = f(x, y + 2)",
+            expect![[r#"
+                function f(a: int, b: int): int
+                {
+                    return a
+                }
+                {
+                    var x = 1
+                    var y = 2
+                    x = y = f(x, y + 2)
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn print_nested_negations_apart() {
+        check(
+            "var x = 1\nvar b = true\nx = -(-x) + +(+x) - -x\nb = !!b",
+            expect![[r#"
+                {
+                    var x = 1
+                    var b = true
+                    x = -(-x) + +(+x) - -x
+                    b = !!b
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn print_folded_constants() {
+        check(
+            "let a = 2\nvar b = a * 3\nlet c = b\nvar d = (5 + 6) * 3 > 2 + 4\nc + a * (1 + a) - b",
+            expect![[r#"
+                {
+                    let a = 2
+                    var b = 6
+                    let c = b
+                    var d = true
+                    c + 6 - b
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn print_conversions() {
+        check(
+            "var i = 1\nvar f = i + 0.5\nf = i\nint(f) / 2",
+            expect![[r#"
+                {
+                    var i = 1
+                    var f = float(i) + 0.5
+                    f = float(i)
+                    int(f) / 2
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn print_compound_assignments_and_increments() {
+        check(
+            "var i = 1\nvar f = 0.5\ni *= 2\nf += i\nf-- * 2 + -++i",
+            expect![[r#"
+                {
+                    var i = 1
+                    var f = 0.5
+                    i = i * 2
+                    f = f + float(i)
+                    f-- * 2.0 + float(-(i = i + 1))
+                }
+            "#]],
+        )
+    }
+
+    #[test]
+    fn print_right_associative_operators() {
+        check(
+            "var a = 2\n(a ** a) ** a + a ** a ** a + -a ** 2 + (-a) ** 2 + a ~/ (a % a)",
+            expect![[r#"
+                {
+                    var a = 2
+                    (a ** a) ** a + a ** a ** a + -a ** 2 + (-a) ** 2 + a ~/ (a % a)
+                }
+            "#]],
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::builtins::lookup_builtin;
+use crate::symbols::FunctionSymbol;
+use crate::symbols::VariableSymbol;
+
+/// The variables and functions visible to the binder, by name. The two do
+/// not share a namespace. Each block gets its own scope whose declarations
+/// shadow those of its parents. A scope outlives a single call to the binder
+/// so that the REPL can keep variables between lines.
+#[derive(Debug, Default, Clone)]
+pub struct BoundScope {
+    parent: Option<Box<BoundScope>>,
+    variables: HashMap<String, VariableSymbol>,
+    functions: HashMap<String, FunctionSymbol>,
+}
+
+impl BoundScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_parent(parent: BoundScope) -> Self {
+        Self {
+            parent: Some(Box::new(parent)),
+            ..Self::default()
+        }
+    }
+
+    pub fn into_parent(self) -> Option<BoundScope> {
+        self.parent.map(|parent| *parent)
+    }
+
+    /// Finds `name` in this scope or the closest enclosing one.
+    pub fn lookup(&self, name: &str) -> Option<&VariableSymbol> {
+        match self.variables.get(name) {
+            Some(variable) => Some(variable),
+            None => self.parent.as_ref()?.lookup(name),
+        }
+    }
+
+    /// Adds `variable` to this scope, returning `false` without changing
+    /// anything if this scope already declares a variable with that name.
+    pub fn try_declare(&mut self, variable: VariableSymbol) -> bool {
+        if self.variables.contains_key(&variable.name) {
+            return false;
+        }
+        self.variables.insert(variable.name.clone(), variable);
+        true
+    }
+
+    /// The variables declared directly in this scope.
+    pub fn variables(&self) -> impl Iterator<Item = &VariableSymbol> {
+        self.variables.values()
+    }
+
+    /// Every variable that `lookup` can find, sorted by name.
+    pub fn visible_variables(&self) -> Vec<&VariableSymbol> {
+        let mut variables = self.variables.values().collect::<Vec<_>>();
+        let mut parent = self.parent.as_deref();
+        while let Some(scope) = parent {
+            for variable in scope.variables.values() {
+                if !self.variables.contains_key(&variable.name)
+                    && !variables.iter().any(|v| v.name == variable.name)
+                {
+                    variables.push(variable);
+                }
+            }
+            parent = scope.parent.as_deref();
+        }
+        variables.sort_by(|a, b| a.name.cmp(&b.name));
+        variables
+    }
+
+    /// Finds the function `name` in this scope or the closest enclosing one,
+    /// falling back to the builtin functions.
+    pub fn lookup_function(&self, name: &str) -> Option<&FunctionSymbol> {
+        match (self.functions.get(name), &self.parent) {
+            (Some(function), _) => Some(function),
+            (None, Some(parent)) => parent.lookup_function(name),
+            (None, None) => lookup_builtin(name),
+        }
+    }
+
+    /// Like `try_declare`, but for functions.
+    pub fn try_declare_function(&mut self, function: FunctionSymbol) -> bool {
+        if self.functions.contains_key(&function.name) {
+            return false;
+        }
+        self.functions.insert(function.name.clone(), function);
+        true
+    }
+}
@@ -0,0 +1,97 @@
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use dumbbrain::object::DumbBrainObject;
+use dumbbrain::span::Span;
+use dumbbrain::types::DumbBrainType;
+
+static NEXT_SYMBOL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A variable known to the binder. Every declaration creates a distinct
+/// symbol, even when the name is reused, so the evaluator can tell them apart.
+#[derive(Debug, Clone)]
+pub struct VariableSymbol {
+    id: usize,
+    pub name: String,
+    /// Declared with `let` rather than `var`.
+    pub read_only: bool,
+    /// Declared inside a function, so that every call has its own copy.
+    pub local: bool,
+    pub kind: DumbBrainType,
+    /// The value of a read-only variable whose initializer is constant, which
+    /// uses of the variable are folded into.
+    pub constant: Option<DumbBrainObject>,
+    /// The name in the declaration, or `None` if the variable does not come
+    /// from the source, like the parameters of builtin functions.
+    pub declaration: Option<Span>,
+}
+
+impl VariableSymbol {
+    pub fn new(
+        name: String,
+        read_only: bool,
+        local: bool,
+        kind: DumbBrainType,
+        declaration: Option<Span>,
+    ) -> Self {
+        Self {
+            id: NEXT_SYMBOL_ID.fetch_add(1, Ordering::Relaxed),
+            name,
+            read_only,
+            local,
+            kind,
+            constant: None,
+            declaration,
+        }
+    }
+}
+
+impl PartialEq for VariableSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for VariableSymbol {}
+
+impl Hash for VariableSymbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// A function known to the binder. Its parameters are read-only local
+/// variables.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionSymbol {
+    id: usize,
+    pub name: String,
+    pub parameters: Vec<VariableSymbol>,
+    pub return_type: DumbBrainType,
+}
+
+impl FunctionSymbol {
+    pub fn new(name: String, parameters: Vec<VariableSymbol>, return_type: DumbBrainType) -> Self {
+        Self {
+            id: NEXT_SYMBOL_ID.fetch_add(1, Ordering::Relaxed),
+            name,
+            parameters,
+            return_type,
+        }
+    }
+
+    /// The type of the function, like `int -> int` or `(int, float) -> bool`.
+    pub fn type_name(&self) -> String {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| parameter.kind.to_string())
+            .collect::<Vec<_>>();
+        match parameters.as_slice() {
+            [parameter] => format!("{} -> {}", parameter, self.return_type),
+            _ => format!("({}) -> {}", parameters.join(", "), self.return_type),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use dumbbrain::diagnostic::Diagnostic;
+use dumbbrain::diagnostic::DiagnosticCode;
+use dumbbrain::span::Span;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RuntimeErrorKind {
+    /// An operation was given values it is not defined for. The binder
+    /// rules this out for trees without errors.
+    TypeMismatch,
+    /// An int was divided by zero, or a float while checked division was
+    /// enabled.
+    DivisionByZero,
+    /// The result of an int operation or conversion did not fit in an int.
+    Overflow,
+    /// Calls nested deeper than the evaluator's maximum call depth.
+    StackOverflow,
+    /// A function read a global before its declaration had run.
+    UninitializedVariable,
+    /// The program called `error`.
+    UserError,
+    /// A string converted to another type did not hold a value of it.
+    InvalidFormat,
+    /// An int was raised to a negative power.
+    NegativeExponent,
+}
+
+impl RuntimeErrorKind {
+    pub fn code(self) -> DiagnosticCode {
+        match self {
+            Self::TypeMismatch => DiagnosticCode::TypeMismatch,
+            Self::DivisionByZero => DiagnosticCode::DivisionByZero,
+            Self::Overflow => DiagnosticCode::Overflow,
+            Self::StackOverflow => DiagnosticCode::StackOverflow,
+            Self::UninitializedVariable => DiagnosticCode::UninitializedVariable,
+            Self::UserError => DiagnosticCode::UserError,
+            Self::InvalidFormat => DiagnosticCode::CannotConvert,
+            Self::NegativeExponent => DiagnosticCode::NegativeExponent,
+        }
+    }
+}
+
+/// A call that was in progress when a runtime error happened.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StackFrame {
+    pub function: String,
+    /// Where the function was called from.
+    pub span: Span,
+}
+
+/// Why evaluation stopped early.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub message: String,
+    /// The expression that failed.
+    pub span: Span,
+    /// The calls in progress when the error happened, innermost first.
+    pub trace: Vec<StackFrame>,
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Diagnostic::from(self.clone()))
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+impl From<RuntimeError> for Diagnostic {
+    /// Reports the error with a label at every call in its trace. Runs of the
+    /// same call, as in deep recursion, get a single label.
+    fn from(error: RuntimeError) -> Self {
+        let mut diagnostic = Diagnostic::error(error.kind.code(), error.span, error.message);
+        let mut frames = error.trace.into_iter().peekable();
+        while let Some(frame) = frames.next() {
+            let mut repeats = 1;
+            while frames.peek() == Some(&frame) {
+                frames.next();
+                repeats += 1;
+            }
+            let message = match repeats {
+                1 => format!("in the call to '{}'", frame.function),
+                _ => format!("in {} calls to '{}'", repeats, frame.function),
+            };
+            diagnostic = diagnostic.with_label(frame.span, message);
+        }
+        diagnostic
+    }
+}
@@ -1,174 +1,456 @@
+use std::collections::HashMap;
+
 use dumbbrain::object::DumbBrainObject;
-use dumbbrain::types::DumbBrainType;
+use dumbbrain::span::Span;
+use dumbbrain_binder::approx_eq_function;
+use dumbbrain_binder::convert;
+use dumbbrain_binder::error_function;
+use dumbbrain_binder::lookup_builtin;
 use dumbbrain_binder::BinaryOperation;
 use dumbbrain_binder::BoundExpression;
 use dumbbrain_binder::BoundExpressionNode;
+use dumbbrain_binder::BoundStatement;
+use dumbbrain_binder::BoundStatementNode;
+use dumbbrain_binder::FunctionSymbol;
+use dumbbrain_binder::OperationError;
 use dumbbrain_binder::UnaryOperation;
+use dumbbrain_binder::VariableSymbol;
+
+mod error;
+pub use error::*;
+
+/// How deeply calls may nest, unless configured otherwise with
+/// `Evaluator::with_max_call_depth`. Calls grow the native stack as they
+/// need to, so this only guards against runaway recursion.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
+/// How much native stack a call needs to have left before it runs, and how
+/// much more it allocates otherwise. A call to a function evaluates its body
+/// through a few recursive functions, which use several KiB in debug builds.
+const STACK_RED_ZONE: usize = 64 * 1024;
+const STACK_GROWTH: usize = 1024 * 1024;
 
-const FLOATING_POINT_DELTA: f64 = 1e-6;
+/// How a lowered block finished: by running off its end, or by returning
+/// from the current function.
+#[derive(Debug, PartialEq, Clone)]
+enum Completion {
+    Normal,
+    Return(Option<DumbBrainObject>),
+}
+
+/// The current value of every variable assigned so far.
+pub type Variables = HashMap<VariableSymbol, DumbBrainObject>;
 
-pub struct Evaluator {
-    bound_tree: BoundExpression,
+/// The body of every function declared so far.
+pub type Functions = HashMap<FunctionSymbol, BoundStatement>;
+
+pub struct Evaluator<'a> {
+    functions: &'a Functions,
+    globals: &'a mut Variables,
+    /// The local variables of every call in progress, innermost last.
+    locals: Vec<Variables>,
+    /// The call in progress for each set of `locals`.
+    calls: Vec<StackFrame>,
+    max_call_depth: usize,
+    checked_division: bool,
+    last_value: Option<DumbBrainObject>,
 }
 
-impl Evaluator {
-    pub fn new(bound_tree: BoundExpression) -> Self {
-        Self { bound_tree }
+impl<'a> Evaluator<'a> {
+    pub fn new(functions: &'a Functions, globals: &'a mut Variables) -> Self {
+        Self {
+            functions,
+            globals,
+            locals: vec![],
+            calls: vec![],
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            checked_division: false,
+            last_value: None,
+        }
+    }
+
+    /// Makes calls nested deeper than `max_call_depth` stop evaluation with
+    /// an error.
+    pub fn with_max_call_depth(mut self, max_call_depth: usize) -> Self {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
+    /// Makes division by zero stop evaluation with an error, instead of
+    /// producing an infinity or NaN.
+    pub fn with_checked_division(mut self, checked_division: bool) -> Self {
+        self.checked_division = checked_division;
+        self
+    }
+
+    /// Executes a lowered tree and returns the value of the last expression
+    /// statement executed, or `None` if there was no such statement.
+    pub fn evaluate(
+        &mut self,
+        bound_tree: &BoundStatement,
+    ) -> Result<Option<DumbBrainObject>, RuntimeError> {
+        self.last_value = None;
+        self.evaluate_block(bound_tree)?;
+        Ok(self.last_value.take())
+    }
+
+    fn error(&self, kind: RuntimeErrorKind, span: &Span, message: String) -> RuntimeError {
+        RuntimeError {
+            kind,
+            message,
+            span: span.clone(),
+            trace: self.calls.iter().rev().cloned().collect(),
+        }
+    }
+
+    /// Runs a block produced by the `Lowerer`, following its jumps.
+    fn evaluate_block(&mut self, block: &BoundStatement) -> Result<Completion, RuntimeError> {
+        let statements = match &block.node {
+            BoundStatementNode::Block { statements } => statements,
+            _ => unreachable!("lowered trees are always blocks"),
+        };
+        let labels = statements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, statement)| match &statement.node {
+                BoundStatementNode::Label { label } => Some((label, index)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut index = 0;
+        while let Some(statement) = statements.get(index) {
+            index += 1;
+            match &statement.node {
+                BoundStatementNode::Expression { expression } => {
+                    // Calls to functions that return nothing are only allowed
+                    // here, where no value is needed.
+                    self.last_value = match &expression.node {
+                        BoundExpressionNode::Call {
+                            function,
+                            arguments,
+                        } => self.evaluate_call(function, arguments, expression)?,
+                        _ => Some(self.evaluate_expression(expression)?),
+                    };
+                }
+                BoundStatementNode::VariableDeclaration {
+                    variable,
+                    initializer,
+                } => {
+                    let value = self.evaluate_expression(initializer)?;
+                    self.variables_for(variable).insert(variable.clone(), value);
+                }
+                BoundStatementNode::Label { .. } => {}
+                BoundStatementNode::Goto { label } => index = labels[label],
+                BoundStatementNode::ConditionalGoto {
+                    label,
+                    condition,
+                    jump_if_true,
+                } => {
+                    if self.evaluate_condition(condition)? == *jump_if_true {
+                        index = labels[label];
+                    }
+                }
+                BoundStatementNode::Return { expression } => {
+                    let value = match expression {
+                        Some(expression) => Some(self.evaluate_expression(expression)?),
+                        None => None,
+                    };
+                    return Ok(Completion::Return(value));
+                }
+                BoundStatementNode::Block { .. }
+                | BoundStatementNode::If { .. }
+                | BoundStatementNode::While { .. }
+                | BoundStatementNode::For { .. }
+                | BoundStatementNode::Break
+                | BoundStatementNode::Continue => {
+                    unreachable!("structured statements are lowered before evaluation")
+                }
+            }
+        }
+        Ok(Completion::Normal)
     }
 
-    pub fn evaluate(&self) -> Option<DumbBrainObject> {
-        self.evaluate_expression(&self.bound_tree)
+    fn evaluate_condition(&mut self, condition: &BoundExpression) -> Result<bool, RuntimeError> {
+        match self.evaluate_expression(condition)? {
+            DumbBrainObject::Boolean(b) => Ok(b),
+            value => Err(self.error(
+                RuntimeErrorKind::TypeMismatch,
+                &condition.span,
+                format!("expected a condition of type bool, found {}", value),
+            )),
+        }
     }
 
-    fn evaluate_expression(&self, expression: &BoundExpression) -> Option<DumbBrainObject> {
+    fn evaluate_expression(
+        &mut self,
+        expression: &BoundExpression,
+    ) -> Result<DumbBrainObject, RuntimeError> {
         match &expression.node {
-            BoundExpressionNode::Literal { value } => value.clone(),
+            BoundExpressionNode::Literal { value } => {
+                Ok(value.clone().expect("literal tokens always carry a value"))
+            }
             BoundExpressionNode::Binary {
                 left,
                 right,
                 operation,
-            } => Some(self.evaluate_binary_expression(left, right, operation, expression)),
+            } => self.evaluate_binary_expression(left, right, operation, expression),
             BoundExpressionNode::Unary { operand, operation } => {
                 self.evaluate_unary_expression(operand, operation, expression)
             }
+            BoundExpressionNode::Variable { variable } => {
+                // A function can run before a global it uses is declared.
+                match self.variables_for(variable).get(variable) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(self.error(
+                        RuntimeErrorKind::UninitializedVariable,
+                        &expression.span,
+                        format!(
+                            "variable '{}' is used before it is initialized",
+                            variable.name
+                        ),
+                    )),
+                }
+            }
+            BoundExpressionNode::Assignment {
+                variable,
+                expression,
+            } => {
+                let value = self.evaluate_expression(expression)?;
+                self.variables_for(variable)
+                    .insert(variable.clone(), value.clone());
+                Ok(value)
+            }
+            BoundExpressionNode::PostfixAssignment {
+                variable,
+                expression,
+            } => {
+                // Reading the variable in `expression` fails if it is not
+                // initialized, so there is always a previous value here.
+                let previous = self.variables_for(variable).get(variable).cloned();
+                let value = self.evaluate_expression(expression)?;
+                self.variables_for(variable).insert(variable.clone(), value);
+                Ok(previous.expect("the variable was read by its new value"))
+            }
+            BoundExpressionNode::Call {
+                function,
+                arguments,
+            } => match self.evaluate_call(function, arguments, expression)? {
+                Some(value) => Ok(value),
+                None => Err(self.error(
+                    RuntimeErrorKind::TypeMismatch,
+                    &expression.span,
+                    format!("function '{}' does not return a value", function.name),
+                )),
+            },
+            BoundExpressionNode::Conversion {
+                expression: operand,
+            } => self.evaluate_conversion(operand, expression),
+            BoundExpressionNode::Error => Err(self.error(
+                RuntimeErrorKind::TypeMismatch,
+                &expression.span,
+                String::from("expression has errors from binding"),
+            )),
         }
     }
 
-    fn evaluate_unary_expression(
+    fn variables_for(&mut self, variable: &VariableSymbol) -> &mut Variables {
+        if variable.local {
+            self.locals
+                .last_mut()
+                .expect("local variables are only used inside calls")
+        } else {
+            self.globals
+        }
+    }
+
+    /// Runs `function` in a fresh set of local variables, returning its
+    /// value, or `None` if it does not return one.
+    fn evaluate_call(
+        &mut self,
+        function: &FunctionSymbol,
+        arguments: &[BoundExpression],
+        expression: &BoundExpression,
+    ) -> Result<Option<DumbBrainObject>, RuntimeError> {
+        if self.locals.len() >= self.max_call_depth {
+            return Err(self.error(
+                RuntimeErrorKind::StackOverflow,
+                &expression.span,
+                format!(
+                    "calling '{}' exceeds the maximum call depth of {}",
+                    function.name, self.max_call_depth
+                ),
+            ));
+        }
+
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.evaluate_expression(argument))
+            .collect::<Result<Vec<_>, _>>()?;
+        if lookup_builtin(&function.name) == Some(function) {
+            return self.evaluate_builtin(function, &arguments, expression);
+        }
+        let locals = function.parameters.iter().cloned().zip(arguments).collect();
+
+        let functions = self.functions;
+        let body = &functions[function];
+        let last_value = self.last_value.take();
+        self.locals.push(locals);
+        self.calls.push(StackFrame {
+            function: function.name.clone(),
+            span: expression.span.clone(),
+        });
+        let completion =
+            stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || self.evaluate_block(body));
+        self.calls.pop();
+        self.locals.pop();
+        self.last_value = last_value;
+
+        // The binder checks that functions returning a value always do.
+        match completion? {
+            Completion::Return(value) => Ok(value),
+            Completion::Normal => Ok(None),
+        }
+    }
+
+    fn evaluate_builtin(
         &self,
+        function: &FunctionSymbol,
+        arguments: &[DumbBrainObject],
+        expression: &BoundExpression,
+    ) -> Result<Option<DumbBrainObject>, RuntimeError> {
+        match arguments {
+            [DumbBrainObject::String(message)] if function == error_function() => Err(self.error(
+                RuntimeErrorKind::UserError,
+                &expression.span,
+                message.clone(),
+            )),
+            [DumbBrainObject::Float(a), DumbBrainObject::Float(b), DumbBrainObject::Float(tolerance)]
+                if function == approx_eq_function() =>
+            {
+                let equal = a == b || (a - b).abs() <= *tolerance;
+                Ok(Some(DumbBrainObject::Boolean(equal)))
+            }
+            _ => Err(self.error(
+                RuntimeErrorKind::TypeMismatch,
+                &expression.span,
+                format!("mismatched arguments for '{}'", function.name),
+            )),
+        }
+    }
+
+    fn evaluate_unary_expression(
+        &mut self,
         operand: &BoundExpression,
         operation: &UnaryOperation,
         expression: &BoundExpression,
-    ) -> Option<DumbBrainObject> {
-        let operand = self.evaluate_expression(&operand);
-        match operation {
-            UnaryOperation::Identity => operand,
-            UnaryOperation::Negation => match expression.kind {
-                DumbBrainType::Number => Some(DumbBrainObject::Number(
-                    -operand.unwrap().try_into_number().unwrap(),
-                )),
-                _ => panic!("unexpected type for {:?}: {:?}", operation, expression.kind),
-            },
-        }
+    ) -> Result<DumbBrainObject, RuntimeError> {
+        let operand = self.evaluate_expression(operand)?;
+        operation.apply(&operand).map_err(|error| match error {
+            OperationError::Overflow => self.error(
+                RuntimeErrorKind::Overflow,
+                &expression.span,
+                format!("integer overflow in {}{}", operation, operand),
+            ),
+            _ => self.error(
+                RuntimeErrorKind::TypeMismatch,
+                &expression.span,
+                format!("mismatched type for {:?}: {}", operation, operand),
+            ),
+        })
     }
 
     fn evaluate_binary_expression(
-        &self,
+        &mut self,
         left: &BoundExpression,
         right: &BoundExpression,
         operation: &BinaryOperation,
         expression: &BoundExpression,
-    ) -> DumbBrainObject {
-        let left = self.evaluate_expression(&left);
-        let right = self.evaluate_expression(&right);
-        match operation {
-            BinaryOperation::Add => match expression.kind {
-                DumbBrainType::Number => DumbBrainObject::Number(
-                    left.unwrap().try_into_number().unwrap()
-                        + right.unwrap().try_into_number().unwrap(),
-                ),
-                _ => panic!("unexpected type for {:?}: {:?}", operation, expression.kind),
-            },
-            BinaryOperation::Subtract => match expression.kind {
-                DumbBrainType::Number => DumbBrainObject::Number(
-                    left.unwrap().try_into_number().unwrap()
-                        - right.unwrap().try_into_number().unwrap(),
-                ),
-                _ => panic!("unexpected type for {:?}: {:?}", operation, expression.kind),
-            },
-            BinaryOperation::Multiply => match expression.kind {
-                DumbBrainType::Number => DumbBrainObject::Number(
-                    left.unwrap().try_into_number().unwrap()
-                        * right.unwrap().try_into_number().unwrap(),
-                ),
-                _ => panic!("unexpected type for {:?}: {:?}", operation, expression.kind),
-            },
-            BinaryOperation::Divide => match expression.kind {
-                DumbBrainType::Number => DumbBrainObject::Number(
-                    left.unwrap().try_into_number().unwrap()
-                        / right.unwrap().try_into_number().unwrap(),
-                ),
-                _ => panic!("unexpected type for {:?}: {:?}", operation, expression.kind),
-            },
-            BinaryOperation::Equality
-            | BinaryOperation::Inequality
-            | BinaryOperation::Less
-            | BinaryOperation::LessEquals
-            | BinaryOperation::Greater
-            | BinaryOperation::GreaterEquals => evaluate_comparison(left, right, *operation),
-            BinaryOperation::LogicalAnd => evaluate_boolean_operation(left, right, *operation),
-            BinaryOperation::LogicalOr => evaluate_boolean_operation(left, right, *operation),
+    ) -> Result<DumbBrainObject, RuntimeError> {
+        let left = self.evaluate_expression(left)?;
+        // `&&` and `||` only evaluate their right operand when the left one
+        // does not already decide the result.
+        match (operation, &left) {
+            (BinaryOperation::LogicalAnd, DumbBrainObject::Boolean(false))
+            | (BinaryOperation::LogicalOr, DumbBrainObject::Boolean(true)) => return Ok(left),
+            _ => {}
         }
-    }
-}
-
-fn evaluate_boolean_operation(
-    left: Option<DumbBrainObject>,
-    right: Option<DumbBrainObject>,
-    operation: BinaryOperation,
-) -> DumbBrainObject {
-    let value = match *left.as_ref().unwrap() {
-        DumbBrainObject::Boolean(b) if right.as_ref().unwrap().is_boolean() => {
-            let c = right.unwrap().try_into_boolean().unwrap();
-            match operation {
-                BinaryOperation::LogicalAnd => b && c,
-                BinaryOperation::LogicalOr => b || c,
-                _ => unreachable!(),
-            }
-        }
-        _ => {
-            panic!(
-                "mismatched types for {:?}: {:?} and {:?}",
-                operation,
-                *left.as_ref().unwrap(),
-                *right.as_ref().unwrap()
-            )
-        }
-    };
-    DumbBrainObject::Boolean(value)
-}
-
-fn evaluate_comparison(
-    left: Option<DumbBrainObject>,
-    right: Option<DumbBrainObject>,
-    operation: BinaryOperation,
-) -> DumbBrainObject {
-    let value = match *left.as_ref().unwrap() {
-        DumbBrainObject::Number(n) if right.as_ref().unwrap().is_number() => {
-            let m = right.unwrap().try_into_number().unwrap();
-            match operation {
-                BinaryOperation::Equality => (n - m).abs() < FLOATING_POINT_DELTA,
-                BinaryOperation::Inequality => (n - m).abs() > FLOATING_POINT_DELTA,
-                BinaryOperation::Less => n < m,
-                BinaryOperation::LessEquals => n <= m,
-                BinaryOperation::Greater => n > m,
-                BinaryOperation::GreaterEquals => n >= m,
-                _ => unreachable!(),
+        let right = self.evaluate_expression(right)?;
+        if let (
+            BinaryOperation::Divide | BinaryOperation::Modulo | BinaryOperation::FloorDivide,
+            DumbBrainObject::Float(divisor),
+        ) = (operation, &right)
+        {
+            if self.checked_division && *divisor == 0.0 {
+                return Err(self.error(
+                    RuntimeErrorKind::DivisionByZero,
+                    &expression.span,
+                    String::from("division by zero"),
+                ));
             }
         }
-        DumbBrainObject::Boolean(b) if right.as_ref().unwrap().is_boolean() => {
-            let c = right.unwrap().try_into_boolean().unwrap();
-            match operation {
-                BinaryOperation::Equality => b == c,
-                BinaryOperation::Inequality => b != c,
-                _ => panic!(
-                    "type mismatch: cannot perform comparison {:?} on Boolean and Boolean",
-                    operation
+        operation.apply(&left, &right).map_err(|error| match error {
+            OperationError::Overflow => self.error(
+                RuntimeErrorKind::Overflow,
+                &expression.span,
+                format!("integer overflow in {} {} {}", left, operation, right),
+            ),
+            OperationError::DivisionByZero => self.error(
+                RuntimeErrorKind::DivisionByZero,
+                &expression.span,
+                String::from("division by zero"),
+            ),
+            OperationError::NegativeExponent => self.error(
+                RuntimeErrorKind::NegativeExponent,
+                &expression.span,
+                format!(
+                    "{} {} {} has no int result, so raise a float to a negative power instead",
+                    left, operation, right
                 ),
-            }
-        }
-        _ => panic!(
-            "type mismatch on ==: {} vs {}",
-            left.as_ref().unwrap(),
-            right.as_ref().unwrap()
-        ),
-    };
-    DumbBrainObject::Boolean(value)
+            ),
+            // Only conversions parse strings.
+            OperationError::TypeMismatch | OperationError::InvalidFormat => self.error(
+                RuntimeErrorKind::TypeMismatch,
+                &expression.span,
+                format!(
+                    "mismatched types for {:?}: {} and {}",
+                    operation, left, right
+                ),
+            ),
+        })
+    }
+
+    fn evaluate_conversion(
+        &mut self,
+        operand: &BoundExpression,
+        expression: &BoundExpression,
+    ) -> Result<DumbBrainObject, RuntimeError> {
+        let value = self.evaluate_expression(operand)?;
+        convert(&value, expression.kind).map_err(|error| match error {
+            OperationError::Overflow => self.error(
+                RuntimeErrorKind::Overflow,
+                &expression.span,
+                format!("{} is out of the range of {}", value, expression.kind),
+            ),
+            OperationError::InvalidFormat => self.error(
+                RuntimeErrorKind::InvalidFormat,
+                &expression.span,
+                format!("cannot parse {} as {}", value, expression.kind),
+            ),
+            _ => self.error(
+                RuntimeErrorKind::TypeMismatch,
+                &expression.span,
+                format!("cannot convert {} to {}", value, expression.kind),
+            ),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use dumbbrain::diagnostic::Diagnostic;
     use dumbbrain_binder::Binder;
+    use dumbbrain_binder::BoundScope;
     use dumbbrain_parser::Parser;
     use expect_test::expect;
     use expect_test::Expect;
@@ -177,20 +459,81 @@ mod tests {
 
     fn check(input: &str, expected: Expect) {
         let tree = Parser::new(input).parse();
-        let bound_tree = Binder::bind_expression(&tree);
-        let value = Evaluator::new(bound_tree).evaluate();
+        let program = Binder::new().bind_compilation_unit(&tree.root);
+        let value = Evaluator::new(&program.functions, &mut Variables::new())
+            .evaluate(&program.statement)
+            .unwrap();
         let formatted = format!("{:#?}", value);
         expected.assert_eq(&formatted);
     }
 
+    /// Evaluates each input in turn, keeping variables between them like the
+    /// REPL does, and returns the last value.
+    fn check_session(inputs: &[&str], expected: Expect) {
+        let mut scope = BoundScope::new();
+        let mut functions = Functions::new();
+        let mut variables = Variables::new();
+        let mut value = None;
+        for input in inputs {
+            let tree = Parser::new(input).parse();
+            let mut binder = Binder::with_scope(scope);
+            let program = binder.bind_compilation_unit(&tree.root);
+            assert!(
+                binder.diagnostics().is_empty(),
+                "{:#?}",
+                binder.diagnostics()
+            );
+            scope = binder.into_scope();
+            functions.extend(program.functions);
+            value = Evaluator::new(&functions, &mut variables)
+                .evaluate(&program.statement)
+                .unwrap();
+        }
+        let formatted = format!("{:#?}", value);
+        expected.assert_eq(&formatted);
+    }
+
+    const MAX_CALL_DEPTH: usize = 10;
+
+    /// Runs the whole pipeline, stopping at the first stage that reports
+    /// anything, and prints what it reported.
+    fn check_diagnostics(input: &str, expected: Expect) {
+        expected.assert_eq(&run_to_diagnostics(input, false));
+    }
+
+    fn run_to_diagnostics(input: &str, checked_division: bool) -> String {
+        let tree = Parser::new(input).parse();
+        let mut diagnostics = tree.diagnostics;
+        if diagnostics.is_empty() {
+            let mut binder = Binder::new();
+            let program = binder.bind_compilation_unit(&tree.root);
+            diagnostics = binder.into_diagnostics();
+            if !diagnostics.has_errors() {
+                let mut variables = Variables::new();
+                let result = Evaluator::new(&program.functions, &mut variables)
+                    .with_max_call_depth(MAX_CALL_DEPTH)
+                    .with_checked_division(checked_division)
+                    .evaluate(&program.statement);
+                if let Err(error) = result {
+                    diagnostics.report(Diagnostic::from(error));
+                }
+            }
+        }
+        diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     #[test]
     fn evaluate_number_literal() {
         check(
             "3",
             expect![[r#"
             Some(
-                Number(
-                    3.0,
+                Int(
+                    3,
                 ),
             )"#]],
         )
@@ -215,8 +558,8 @@ mod tests {
             "3 + 4",
             expect![[r#"
             Some(
-                Number(
-                    7.0,
+                Int(
+                    7,
                 ),
             )"#]],
         )
@@ -228,8 +571,8 @@ mod tests {
             "1 - 2",
             expect![[r#"
             Some(
-                Number(
-                    -1.0,
+                Int(
+                    -1,
                 ),
             )"#]],
         )
@@ -241,26 +584,267 @@ mod tests {
             "2 * 4",
             expect![[r#"
             Some(
-                Number(
-                    8.0,
+                Int(
+                    8,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_number_literal_formats() {
+        check(
+            "0x10 + 0b11 * 1_000 - 2.5e2",
+            expect![[r#"
+            Some(
+                Float(
+                    2766.0,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_string_concatenation() {
+        check(
+            r#""Hello, " + "world\u{21}""#,
+            expect![[r#"
+            Some(
+                String(
+                    "Hello, world!",
                 ),
             )"#]],
         )
     }
 
+    #[test]
+    fn evaluate_string_comparison() {
+        check(
+            r#""apple" < "banana" && "a" + "b" == "ab""#,
+            expect![[r#"
+            Some(
+                Boolean(
+                    true,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn report_unterminated_string() {
+        check_diagnostics(
+            r#""abc"#,
+            expect!["error[DB0008] at 1:1: unterminated string literal"],
+        )
+    }
+
     #[test]
     fn evaluate_division() {
         check(
-            "5 / 6",
+            "5.0 / 6",
             expect![[r#"
             Some(
-                Number(
+                Float(
                     0.8333333333333334,
                 ),
             )"#]],
         )
     }
 
+    #[test]
+    fn int_division_rounds_towards_zero() {
+        check(
+            "var n = -7\nn / 2",
+            expect![[r#"
+            Some(
+                Int(
+                    -3,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_modulo_floor_division_and_power() {
+        check(
+            "var n = -7\nn % 3 == 2 && n ~/ 2 == -4 && -2 ** 2 == -4 && 2 ** 3 ** 2 == 512",
+            expect![[r#"
+                Some(
+                    Boolean(
+                        true,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_float_modulo_floor_division_and_power() {
+        check(
+            "var x = -7.5\nx % 2 + x ~/ 2 + 2 ** 0.5",
+            expect![[r#"
+            Some(
+                Float(
+                    -2.085786437626905,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn floor_division_and_modulo_recombine() {
+        check(
+            "var ok = true\nfor a = -7 to 7 {\n  for b = -3 to 3 {\n    if b != 0 ok = ok && (a ~/ b) * b + a % b == a\n  }\n}\nok",
+            expect![[r#"
+                Some(
+                    Boolean(
+                        true,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn report_negative_int_exponents() {
+        check_diagnostics(
+            "var n = -1\n2 ** n",
+            expect!["error[DB0031] at 2:1: 2 ** -1 has no int result, so raise a float to a negative power instead"],
+        );
+        check_diagnostics(
+            "var n = -1\n0 ** n",
+            expect!["error[DB0031] at 2:1: 0 ** -1 has no int result, so raise a float to a negative power instead"],
+        )
+    }
+
+    #[test]
+    fn report_power_overflow() {
+        check_diagnostics(
+            "var n = 2\nn ** 64",
+            expect!["error[DB0026] at 2:1: integer overflow in 2 ** 64"],
+        )
+    }
+
+    #[test]
+    fn report_increment_overflow() {
+        check_diagnostics(
+            "var n = 9223372036854775807\nn++",
+            expect!["error[DB0026] at 2:1: integer overflow in 9223372036854775807 + 1"],
+        )
+    }
+
+    #[test]
+    fn evaluate_bitwise_operators() {
+        check(
+            "var flags = 0b1010\nflags & ~0b10 | 1 << 4 ^ flags >> 1",
+            expect![[r#"
+                Some(
+                    Int(
+                        29,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_compound_assignments() {
+        check(
+            "var x = 10\nvar n = 0\nfor i = 1 to 4 { n += i }\nx += 5\nx -= n\nx *= 3\nvar y = (x /= 2)\nx * 100 + y",
+            expect![[r#"
+                Some(
+                    Int(
+                        707,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn increments_evaluate_to_new_and_old_values() {
+        check(
+            "var i = 5\nvar a = i++\nvar b = ++i\nvar c = i--\nvar d = --i\nvar f = 0.5\nvar g = f++ + f\na * 10000 + b * 1000 + c * 100 + d * 10 + i + g",
+            expect![[r#"
+                Some(
+                    Float(
+                        57757.0,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_mixed_arithmetic() {
+        check(
+            "var n = 7\nn / 2.0 + n",
+            expect![[r#"
+            Some(
+                Float(
+                    10.5,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_conversions() {
+        check(
+            "var f = -2.7\nint(f) * 10 + int(float(3))",
+            expect![[r#"
+            Some(
+                Int(
+                    -17,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_string_conversions() {
+        check(
+            "var s = \"4\" + string(2)\nvar x: number = number(s + \".5\")\nstring(int(s) + 1) + string(x) + string(bool(\"true\"))",
+            expect![[r#"
+                Some(
+                    String(
+                        "4342.5true",
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn report_failed_parses() {
+        check_diagnostics(
+            "var s = \"4 2\"\nint(s)",
+            expect![[r#"error[DB0025] at 2:1: cannot parse "4 2" as int"#]],
+        );
+        check_diagnostics(
+            "var s = \"99999999999999999999\"\nint(s)",
+            expect![[r#"error[DB0026] at 2:1: "99999999999999999999" is out of the range of int"#]],
+        )
+    }
+
+    #[test]
+    fn report_integer_overflow() {
+        check_diagnostics(
+            "var n = 9223372036854775807\nn + 1",
+            expect!["error[DB0026] at 2:1: integer overflow in 9223372036854775807 + 1"],
+        )
+    }
+
+    #[test]
+    fn report_conversion_overflow() {
+        check_diagnostics(
+            "var f = 1e19\nint(f)",
+            expect!["error[DB0026] at 2:1: 1e19 is out of the range of int"],
+        )
+    }
+
+    #[test]
+    fn int_division_by_zero_is_always_an_error() {
+        check_diagnostics(
+            "var zero = 0\n1 / zero",
+            expect!["error[DB0022] at 2:1: division by zero"],
+        )
+    }
+
     #[test]
     fn evaluate_equality() {
         check(
@@ -274,6 +858,45 @@ mod tests {
         )
     }
 
+    #[test]
+    fn floats_compare_exactly() {
+        check(
+            "var a = 0.1\na + 0.2 == 0.3",
+            expect![[r#"
+            Some(
+                Boolean(
+                    false,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn nan_is_unequal_to_itself() {
+        check(
+            "var zero = 0.0\nlet nan = zero / zero\nnan != nan && !(nan == nan)",
+            expect![[r#"
+                Some(
+                    Boolean(
+                        true,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_approximate_equality() {
+        check(
+            "var a = 0.1\napproxEq(a + 0.2, 0.3, 1e-9) && !approxEq(a, 0.2, 0.05) && approxEq(1, 1.0, 0)",
+            expect![[r#"
+                Some(
+                    Boolean(
+                        true,
+                    ),
+                )"#]],
+        )
+    }
+
     #[test]
     fn evaluate_inequality() {
         check(
@@ -312,4 +935,476 @@ mod tests {
             )"#]],
         )
     }
+
+    #[test]
+    fn evaluate_logical_not() {
+        check(
+            "!(1 > 2)",
+            expect![[r#"
+            Some(
+                Boolean(
+                    true,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_logical_or() {
+        check(
+            "false || 1 < 2",
+            expect![[r#"
+            Some(
+                Boolean(
+                    true,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_logical_xor() {
+        check(
+            "true ^^ true",
+            expect![[r#"
+            Some(
+                Boolean(
+                    false,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_logical_precedence() {
+        check(
+            "true || false && false ^^ true",
+            expect![[r#"
+            Some(
+                Boolean(
+                    true,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn logical_and_skips_right_operand() {
+        check(
+            "false && f()\nvar x = true\nfunction f(): bool { return x }",
+            expect![[r#"
+            Some(
+                Boolean(
+                    false,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn logical_or_skips_right_operand() {
+        check(
+            "true || f()\nvar x = true\nfunction f(): bool { return x }",
+            expect![[r#"
+            Some(
+                Boolean(
+                    true,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn logical_and_evaluates_right_operand_when_needed() {
+        check_diagnostics(
+            "true && f()\nvar x = true\nfunction f(): bool { return x }",
+            expect![[r#"
+                error[DB0020] at 3:29: variable 'x' is used before it is initialized
+                  1:9: in the call to 'f'"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_chained_assignment() {
+        check(
+            "var a = 0\nvar b = 0\n(a = b = 5) + a * b",
+            expect![[r#"
+            Some(
+                Int(
+                    30,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn variables_persist_between_inputs() {
+        check_session(
+            &["var x = 10", "let y = x * 2", "x = y + x"],
+            expect![[r#"
+            Some(
+                Int(
+                    30,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn last_expression_statement_is_the_result() {
+        check(
+            "var a = 1\n{ var b = a + 1\na = b * 10 }\nlet c = 0",
+            expect![[r#"
+            Some(
+                Int(
+                    20,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_shadowed_variables() {
+        check(
+            "let a = 1\n{ let a = 2\na }\n a",
+            expect![[r#"
+            Some(
+                Int(
+                    1,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn program_without_expressions_has_no_value() {
+        check("let a = 1", expect!["None"])
+    }
+
+    #[test]
+    fn evaluate_if_else() {
+        check(
+            "var a = 0\nif 1 > 2 a = 1 else if 2 > 1 a = 2 else a = 3\na",
+            expect![[r#"
+                Some(
+                    Int(
+                        2,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_while() {
+        check(
+            "var i = 0\nvar sum = 0\nwhile i < 5 { i = i + 1\nsum = sum + i }\nsum",
+            expect![[r#"
+                Some(
+                    Int(
+                        15,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_for() {
+        check(
+            "var sum = 0\nfor i = 1 to 10 sum = sum + i\nsum",
+            expect![[r#"
+            Some(
+                Int(
+                    55,
+                ),
+            )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_for_up_to_the_largest_int() {
+        check(
+            "var n = 0\nfor i = 9223372036854775806 to 9223372036854775807 n = n + 1\nn",
+            expect![[r#"
+                Some(
+                    Int(
+                        2,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_break_and_continue() {
+        check(
+            "var sum = 0\nfor i = 1 to 100 {\n  if i == 3 continue\n  if i > 5 break\n  sum = sum + i\n}\nsum",
+            expect![[r#"
+                Some(
+                    Int(
+                        12,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn break_leaves_only_the_innermost_loop() {
+        check(
+            "var n = 0\nfor i = 1 to 3 { while true { break }\nn = n + 1 }\nn",
+            expect![[r#"
+                Some(
+                    Int(
+                        3,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_function_call() {
+        check(
+            "function add(a: int, b: int): int { return a + b }\nadd(add(1, 2), 3)",
+            expect![[r#"
+                Some(
+                    Int(
+                        6,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_recursion() {
+        check(
+            "function fib(n: int): int {\n  if n < 2 return n\n  return fib(n - 1) + fib(n - 2)\n}\nfib(15)",
+            expect![[r#"
+                Some(
+                    Int(
+                        610,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn return_leaves_loops() {
+        check(
+            "function above(n: int): int {\n  for i = 0 to n if i * i > n return i\n  return -1\n}\nabove(50)",
+            expect![[r#"
+                Some(
+                    Int(
+                        8,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn calls_have_their_own_locals() {
+        check(
+            "function f(n: int): int {\n  var x = n\n  if n > 0 f(n - 1)\n  return x\n}\nf(3)",
+            expect![[r#"
+                Some(
+                    Int(
+                        3,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn functions_update_globals() {
+        check(
+            "var count = 0\nfunction bump() { count = count + 1 }\nbump()\nbump()\ncount",
+            expect![[r#"
+                Some(
+                    Int(
+                        2,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn void_call_has_no_value() {
+        check("function f() { }\nf()", expect!["None"])
+    }
+
+    #[test]
+    fn functions_persist_between_inputs() {
+        check_session(
+            &[
+                "function square(n: int): int { return n * n }",
+                "let x = square(3)",
+                "square(x)",
+            ],
+            expect![[r#"
+                Some(
+                    Int(
+                        81,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn evaluate_lambdas() {
+        check(
+            "let inc = (x) => x + 1\nlet fact = (n) => {\n  var total = 1\n  for i = 2 to n { total *= i }\n  return total\n}\nfact(inc(3))",
+            expect![[r#"
+                Some(
+                    Int(
+                        24,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn lambdas_persist_between_inputs() {
+        check_session(
+            &[
+                "let twice = (x) => x * 2.0",
+                "function f(n: float): float { let g = (m) => twice(m) - 1.0\nreturn g(n) }",
+                "f(twice(1.5))",
+            ],
+            expect![[r#"
+                Some(
+                    Float(
+                        5.0,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn report_stack_overflow() {
+        check_diagnostics(
+            "function f(n: int): int { return f(n + 1) }\nf(0)",
+            expect![[r#"
+                error[DB0019] at 1:34: calling 'f' exceeds the maximum call depth of 10
+                  1:34: in 9 calls to 'f'
+                  2:1: in the call to 'f'"#]],
+        )
+    }
+
+    #[test]
+    fn deep_recursion_does_not_overflow_the_native_stack() {
+        let tree = Parser::new("function f(n: int): int { return f(n + 1) }\nf(0)").parse();
+        let program = Binder::new().bind_compilation_unit(&tree.root);
+        let mut variables = Variables::new();
+        let error = Evaluator::new(&program.functions, &mut variables)
+            .evaluate(&program.statement)
+            .unwrap_err();
+        assert_eq!(error.kind, RuntimeErrorKind::StackOverflow);
+        assert_eq!(error.trace.len(), DEFAULT_MAX_CALL_DEPTH);
+    }
+
+    #[test]
+    fn evaluate_deep_recursion() {
+        check(
+            "function sum(n: int): int {\n  if n == 0 return 0\n  return n + sum(n - 1)\n}\nsum(5000)",
+            expect![[r#"
+                Some(
+                    Int(
+                        12502500,
+                    ),
+                )"#]],
+        )
+    }
+
+    #[test]
+    fn report_user_error() {
+        check_diagnostics(
+            "function check(n: int) { if n < 0 error(\"negative\") }\nfunction f(): int { check(-1)\nreturn 1 }\nf()",
+            expect![[r#"
+                error[DB0024] at 1:35: negative
+                  2:21: in the call to 'check'
+                  4:1: in the call to 'f'"#]],
+        )
+    }
+
+    #[test]
+    fn report_checked_division_by_zero() {
+        let input = "var zero = 0.0\n1.0 / zero";
+        expect!["error[DB0022] at 2:1: division by zero"]
+            .assert_eq(&run_to_diagnostics(input, true));
+        check(
+            input,
+            expect![[r#"
+            Some(
+                Float(
+                    inf,
+                ),
+            )"#]],
+        );
+        // Constant divisors are left for the evaluator to check as well.
+        expect![[r#"
+            warning[DB0022] at 1:1: division by zero
+            error[DB0022] at 1:1: division by zero"#]]
+        .assert_eq(&run_to_diagnostics("1.0 / 0.0", true));
+        expect![[r#"
+            warning[DB0022] at 2:1: division by zero
+            error[DB0022] at 2:1: division by zero"#]]
+        .assert_eq(&run_to_diagnostics("let zero = 0.0\n1.0 / zero", true));
+        expect![[r#"
+            warning[DB0022] at 1:1: division by zero
+            error[DB0022] at 1:1: division by zero"#]]
+        .assert_eq(&run_to_diagnostics("1.0 % 0.0", true));
+        expect![[r#"
+            warning[DB0022] at 2:1: division by zero
+            error[DB0022] at 2:1: division by zero"#]]
+        .assert_eq(&run_to_diagnostics("let zero = 0.0\n1.0 ~/ zero", true));
+        check(
+            "1.0 / 0.0",
+            expect![[r#"
+            Some(
+                Float(
+                    inf,
+                ),
+            )"#]],
+        );
+    }
+
+    #[test]
+    fn report_uninitialized_global() {
+        check_diagnostics(
+            "f()\nvar x = 1\nfunction f(): int { return x }",
+            expect![[r#"
+                error[DB0020] at 3:28: variable 'x' is used before it is initialized
+                  1:1: in the call to 'f'"#]],
+        )
+    }
+
+    #[test]
+    fn report_undefined_name() {
+        check_diagnostics("x + 1", expect!["error[DB0011] at 1:1: undefined name 'x'"])
+    }
+
+    #[test]
+    fn report_lexer_and_parser_diagnostics() {
+        check_diagnostics(
+            "1 + $",
+            expect![[r#"
+            error[DB0001] at 1:5: bad character in input: '$'
+            error[DB0002] at 1:5: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found BadToken"#]],
+        )
+    }
+
+    #[test]
+    fn report_malformed_number() {
+        check_diagnostics(
+            "1 + 0x",
+            expect!["error[DB0007] at 1:5: expected digits after `0x`"],
+        )
+    }
+
+    #[test]
+    fn report_binder_diagnostics() {
+        check_diagnostics(
+            "-true + (1 + false)",
+            expect![[r#"
+            error[DB0003] at 1:1: operator '-' is not defined for type bool
+            error[DB0004] at 1:12: operator '+' is not defined for types int and bool"#]],
+        )
+    }
 }
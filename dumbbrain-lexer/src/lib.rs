@@ -1,35 +1,55 @@
 use std::iter::Peekable;
 use std::str::CharIndices;
 
+use dumbbrain::diagnostic::Diagnostic;
+use dumbbrain::diagnostic::DiagnosticBag;
+use dumbbrain::diagnostic::DiagnosticCode;
 use dumbbrain::object::DumbBrainObject;
 use span::Span;
 use syntax::check_keyword;
 use syntax::SyntaxKind;
 use token::Token;
+use token::Trivia;
 
-pub mod span;
+pub use dumbbrain::span;
 pub mod syntax;
 pub mod token;
 
 pub struct Lexer<'s> {
     source: Peekable<CharIndices<'s>>,
+    source_length: usize,
+    reached_end_of_file: bool,
+    lookahead: Option<Token>,
     line: usize,
     column_offset: usize,
     token_start_line: usize,
     token_start_column_offset: usize,
+    diagnostics: DiagnosticBag,
 }
 
 impl<'s> Lexer<'s> {
     pub fn new(text: &'s str) -> Self {
         Self {
             source: text.char_indices().peekable(),
+            source_length: text.len(),
+            reached_end_of_file: false,
+            lookahead: None,
             line: 1,
             column_offset: 1,
             token_start_line: 1,
             token_start_column_offset: 1,
+            diagnostics: DiagnosticBag::new(),
         }
     }
 
+    pub fn diagnostics(&self) -> &DiagnosticBag {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> DiagnosticBag {
+        self.diagnostics
+    }
+
     fn advance(&mut self) -> Option<(usize, char)> {
         let (i, c) = self.source.next()?;
         match c {
@@ -52,34 +72,325 @@ impl<'s> Lexer<'s> {
             last_column: self.column_offset,
         }
     }
-}
 
-impl<'s> Iterator for Lexer<'s> {
-    type Item = Token;
+    /// The span from the given position up to the current one.
+    fn span_from(&self, line: usize, column: usize) -> Span {
+        Span {
+            first_line: line,
+            first_column: column,
+            last_line: self.line,
+            last_column: self.column_offset,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.token_start_line = self.line;
-        self.token_start_column_offset = self.column_offset;
-        match self.advance()? {
-            (start, c) if c.is_ascii_digit() => {
-                let mut lexeme = c.to_string();
-                while let Some(&(_, c)) = self.source.peek() {
-                    if !c.is_ascii_digit() {
-                        break;
-                    }
+    fn peek_char(&mut self) -> Option<char> {
+        self.source.peek().map(|&(_, c)| c)
+    }
+
+    fn peek_second_char(&self) -> Option<char> {
+        let mut lookahead = self.source.clone();
+        lookahead.next();
+        lookahead.next().map(|(_, c)| c)
+    }
+
+    /// Consumes characters into `lexeme` for as long as `predicate` holds.
+    fn take_while(&mut self, lexeme: &mut String, predicate: impl Fn(char) -> bool) {
+        while let Some(c) = self.peek_char() {
+            if !predicate(c) {
+                break;
+            }
+            self.advance();
+            lexeme.push(c);
+        }
+    }
+
+    /// Lexes a decimal literal with an optional fraction and exponent, or an
+    /// integer with a `0x`, `0o` or `0b` prefix. Underscores may separate
+    /// digits anywhere after the first one. Literals with a fraction or an
+    /// exponent are floats and all others are ints. Malformed literals are
+    /// reported and produce a token without a value.
+    fn lex_number(&mut self, start: usize, first: char) -> Token {
+        let mut lexeme = first.to_string();
+        let radix = match (first, self.peek_char()) {
+            ('0', Some('x' | 'X')) => 16,
+            ('0', Some('o' | 'O')) => 8,
+            ('0', Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+
+        let value = if radix == 10 {
+            self.lex_decimal_digits(&mut lexeme)
+        } else {
+            lexeme.push(self.advance().unwrap().1);
+            self.lex_radix_digits(&mut lexeme, radix)
+        };
+
+        let value = match value {
+            Ok(value) => Some(value),
+            Err(message) => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::InvalidNumber,
+                    self.span(),
+                    message,
+                ));
+                None
+            }
+        };
+        Token::new(SyntaxKind::NumberToken, start, lexeme, value, self.span())
+    }
+
+    fn lex_decimal_digits(&mut self, lexeme: &mut String) -> Result<DumbBrainObject, String> {
+        let is_digit = |c: char| c.is_ascii_digit() || c == '_';
+        self.take_while(lexeme, is_digit);
+
+        let mut is_float = false;
+        if self.peek_char() == Some('.')
+            && self.peek_second_char().is_some_and(|c| c.is_ascii_digit())
+        {
+            is_float = true;
+            lexeme.push(self.advance().unwrap().1);
+            self.take_while(lexeme, is_digit);
+        }
+
+        let mut missing_exponent = false;
+        if let Some('e' | 'E') = self.peek_char() {
+            is_float = true;
+            lexeme.push(self.advance().unwrap().1);
+            if let Some('+' | '-') = self.peek_char() {
+                lexeme.push(self.advance().unwrap().1);
+            }
+            let exponent_start = lexeme.len();
+            self.take_while(lexeme, is_digit);
+            missing_exponent = !lexeme[exponent_start..].contains(|c: char| c.is_ascii_digit());
+        }
+
+        let suffix_start = lexeme.len();
+        self.take_while(lexeme, |c| c.is_alphanumeric() || c == '_');
+        if suffix_start < lexeme.len() {
+            return Err(format!(
+                "invalid suffix `{}` on number literal",
+                &lexeme[suffix_start..]
+            ));
+        }
+        if missing_exponent {
+            return Err(String::from(
+                "expected digits in the exponent of number literal",
+            ));
+        }
+
+        let digits = lexeme.replace('_', "");
+        if !is_float {
+            return digits
+                .parse()
+                .map(DumbBrainObject::Int)
+                .map_err(|_| String::from("integer literal is too large"));
+        }
+        let value: f64 = digits.parse().unwrap();
+        if value.is_finite() {
+            Ok(DumbBrainObject::Float(value))
+        } else {
+            Err(String::from("float literal is too large"))
+        }
+    }
+
+    /// Lexes a string literal whose opening quote has already been consumed.
+    /// Strings may not span lines. Invalid escapes and a missing closing
+    /// quote are reported and produce a token without a value.
+    fn lex_string(&mut self, start: usize) -> Token {
+        let mut lexeme = String::from('"');
+        let mut value = String::new();
+        let mut valid = true;
+        loop {
+            match self.peek_char() {
+                None | Some('\n') | Some('\r') => {
+                    self.diagnostics.report(Diagnostic::error(
+                        DiagnosticCode::UnterminatedString,
+                        self.span(),
+                        String::from("unterminated string literal"),
+                    ));
+                    valid = false;
+                    break;
+                }
+                Some('"') => {
+                    self.advance();
+                    lexeme.push('"');
+                    break;
+                }
+                Some('\\') => match self.lex_escape(&mut lexeme) {
+                    Some(c) => value.push(c),
+                    None => valid = false,
+                },
+                Some(c) => {
                     self.advance();
                     lexeme.push(c);
+                    value.push(c);
                 }
+            }
+        }
 
-                let value = DumbBrainObject::Number(lexeme.parse().unwrap());
-                Some(Token::new(
-                    SyntaxKind::NumberToken,
-                    start,
-                    lexeme,
-                    value,
+        let value = valid.then_some(DumbBrainObject::String(value));
+        Token::new(SyntaxKind::StringToken, start, lexeme, value, self.span())
+    }
+
+    /// Lexes one escape sequence, returning the character it stands for, or
+    /// `None` after reporting why it is invalid.
+    fn lex_escape(&mut self, lexeme: &mut String) -> Option<char> {
+        let (line, column) = (self.line, self.column_offset);
+        self.advance();
+        lexeme.push('\\');
+
+        let escaped = match self.peek_char() {
+            Some('n') => Some('\n'),
+            Some('t') => Some('\t'),
+            Some('"') => Some('"'),
+            Some('\\') => Some('\\'),
+            Some('u') => {
+                self.advance();
+                lexeme.push('u');
+                return self.lex_unicode_escape(lexeme, line, column);
+            }
+            // Leave the end of the line for the string to report as missing
+            // its closing quote.
+            None | Some('\n') | Some('\r') => return None,
+            Some(_) => None,
+        };
+        let c = self.advance().unwrap().1;
+        lexeme.push(c);
+        if escaped.is_none() {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::InvalidEscape,
+                self.span_from(line, column),
+                format!("unknown escape sequence `\\{}`", c),
+            ));
+        }
+        escaped
+    }
+
+    /// Lexes the `{XXXX}` part of a `\\u{XXXX}` escape, which may have up to six
+    /// hexadecimal digits.
+    fn lex_unicode_escape(
+        &mut self,
+        lexeme: &mut String,
+        line: usize,
+        column: usize,
+    ) -> Option<char> {
+        let mut digits = String::new();
+        let mut closed = false;
+        if self.peek_char() == Some('{') {
+            self.advance();
+            lexeme.push('{');
+            self.take_while(&mut digits, |c| c.is_ascii_hexdigit());
+            lexeme.push_str(&digits);
+            if self.peek_char() == Some('}') {
+                self.advance();
+                lexeme.push('}');
+                closed = true;
+            }
+        }
+
+        let c = if closed && (1..=6).contains(&digits.len()) {
+            u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+        } else {
+            None
+        };
+        if c.is_none() {
+            self.diagnostics.report(Diagnostic::error(
+                DiagnosticCode::InvalidEscape,
+                self.span_from(line, column),
+                String::from("invalid unicode escape, expected `\\u{...}` with 1 to 6 hex digits naming a character"),
+            ));
+        }
+        c
+    }
+
+    /// Lexes a block comment whose opening `/` has already been consumed.
+    /// Block comments nest, so every `/*` inside needs its own `*/`.
+    fn lex_block_comment(&mut self, start: usize) -> Token {
+        let mut lexeme = String::from("/");
+        lexeme.push(self.advance().unwrap().1);
+        let mut depth = 1;
+        loop {
+            match (self.peek_char(), self.peek_second_char()) {
+                (Some('/'), Some('*')) => depth += 1,
+                (Some('*'), Some('/')) => depth -= 1,
+                (Some(_), _) => {
+                    lexeme.push(self.advance().unwrap().1);
+                    continue;
+                }
+                (None, _) => {
+                    self.diagnostics.report(Diagnostic::error(
+                        DiagnosticCode::UnterminatedComment,
+                        self.span(),
+                        String::from("unterminated block comment"),
+                    ));
+                    break;
+                }
+            }
+            lexeme.push(self.advance().unwrap().1);
+            lexeme.push(self.advance().unwrap().1);
+            if depth == 0 {
+                break;
+            }
+        }
+        Token::new(
+            SyntaxKind::BlockCommentTrivia,
+            start,
+            lexeme,
+            None,
+            self.span(),
+        )
+    }
+
+    fn lex_radix_digits(
+        &mut self,
+        lexeme: &mut String,
+        radix: u32,
+    ) -> Result<DumbBrainObject, String> {
+        let digits_start = lexeme.len();
+        self.take_while(lexeme, |c| c.is_alphanumeric() || c == '_');
+        let digits = lexeme[digits_start..].replace('_', "");
+
+        if digits.is_empty() {
+            return Err(format!("expected digits after `{}`", &lexeme[..2]));
+        }
+        if let Some(c) = digits.chars().find(|c| !c.is_digit(radix)) {
+            return Err(format!(
+                "invalid digit `{}` in base {} number literal",
+                c, radix
+            ));
+        }
+
+        i64::from_str_radix(&digits, radix)
+            .map(DumbBrainObject::Int)
+            .map_err(|_| String::from("integer literal is too large"))
+    }
+}
+
+impl<'s> Lexer<'s> {
+    /// Lexes the next token, or the next piece of trivia as a token of one of
+    /// the trivia kinds.
+    fn lex_token(&mut self) -> Option<Token> {
+        self.token_start_line = self.line;
+        self.token_start_column_offset = self.column_offset;
+        let next = match self.advance() {
+            Some(next) => next,
+            None if self.reached_end_of_file => return None,
+            None => {
+                self.reached_end_of_file = true;
+                return Some(Token::new(
+                    SyntaxKind::EndOfFileToken,
+                    self.source_length,
+                    String::new(),
+                    None,
                     self.span(),
-                ))
+                ));
             }
+        };
+        match next {
+            (start, c) if c.is_ascii_digit() => Some(self.lex_number(start, c)),
+            (start, '"') => Some(self.lex_string(start)),
             (start, c) if c.is_alphabetic() => {
                 let mut lexeme = c.to_string();
                 while let Some(&(_, c)) = self.source.peek() {
@@ -99,44 +410,107 @@ impl<'s> Iterator for Lexer<'s> {
                 Some(Token::new(kind, start, lexeme, value, self.span()))
             }
             (start, c) if c.is_whitespace() => {
+                // A line break ends the run, so that trailing trivia can stop
+                // at the end of the line.
                 let mut lexeme = c.to_string();
-                while let Some(&(_, c)) = self.source.peek() {
-                    if !c.is_whitespace() {
-                        break;
+                if c != '\n' {
+                    while let Some(&(_, c)) = self.source.peek() {
+                        if !c.is_whitespace() {
+                            break;
+                        }
+                        self.advance();
+                        lexeme.push(c);
+                        if c == '\n' {
+                            break;
+                        }
                     }
-                    self.advance();
-                    lexeme.push(c);
                 }
 
                 Some(Token::new(
-                    SyntaxKind::WhitespaceToken,
+                    SyntaxKind::WhitespaceTrivia,
                     start,
                     lexeme,
                     None,
                     self.span(),
                 ))
             }
-            (pos, '+') => Some(Token::new(
-                SyntaxKind::PlusToken,
-                pos,
-                String::from("+"),
-                None,
-                self.span(),
-            )),
-            (pos, '-') => Some(Token::new(
-                SyntaxKind::MinusToken,
-                pos,
-                String::from("-"),
-                None,
-                self.span(),
-            )),
-            (pos, '*') => Some(Token::new(
-                SyntaxKind::StarToken,
+            (pos, '+') => {
+                let (kind, literal) = match self.peek_char() {
+                    Some('=') => (SyntaxKind::PlusEqualsToken, "+="),
+                    Some('+') => (SyntaxKind::PlusPlusToken, "++"),
+                    _ => (SyntaxKind::PlusToken, "+"),
+                };
+                if literal.len() == 2 {
+                    self.advance();
+                }
+                Some(Token::new(kind, pos, literal.into(), None, self.span()))
+            }
+            (pos, '-') => {
+                let (kind, literal) = match self.peek_char() {
+                    Some('=') => (SyntaxKind::MinusEqualsToken, "-="),
+                    Some('-') => (SyntaxKind::MinusMinusToken, "--"),
+                    _ => (SyntaxKind::MinusToken, "-"),
+                };
+                if literal.len() == 2 {
+                    self.advance();
+                }
+                Some(Token::new(kind, pos, literal.into(), None, self.span()))
+            }
+            (pos, '*') => {
+                let (kind, literal) = match self.peek_char() {
+                    Some('=') => (SyntaxKind::StarEqualsToken, "*="),
+                    Some('*') => (SyntaxKind::StarStarToken, "**"),
+                    _ => (SyntaxKind::StarToken, "*"),
+                };
+                if literal.len() == 2 {
+                    self.advance();
+                }
+                Some(Token::new(kind, pos, literal.into(), None, self.span()))
+            }
+            (pos, '%') => Some(Token::new(
+                SyntaxKind::PercentToken,
                 pos,
-                String::from("*"),
+                String::from("%"),
                 None,
                 self.span(),
             )),
+            // `//` starts a comment, so floor division is written `~/`. A
+            // comment right after `~` still starts at its slash.
+            (pos, '~')
+                if self.peek_char() == Some('/')
+                    && !matches!(self.peek_second_char(), Some('*') | Some('/')) =>
+            {
+                self.advance();
+                Some(Token::new(
+                    SyntaxKind::TildeSlashToken,
+                    pos,
+                    "~/".into(),
+                    None,
+                    self.span(),
+                ))
+            }
+            (start, '/') if self.peek_char() == Some('/') => {
+                let mut lexeme = String::from("/");
+                self.take_while(&mut lexeme, |c| c != '\n' && c != '\r');
+                Some(Token::new(
+                    SyntaxKind::LineCommentTrivia,
+                    start,
+                    lexeme,
+                    None,
+                    self.span(),
+                ))
+            }
+            (start, '/') if self.peek_char() == Some('*') => Some(self.lex_block_comment(start)),
+            (pos, '/') if self.peek_char() == Some('=') => {
+                self.advance();
+                Some(Token::new(
+                    SyntaxKind::SlashEqualsToken,
+                    pos,
+                    "/=".into(),
+                    None,
+                    self.span(),
+                ))
+            }
             (pos, '/') => Some(Token::new(
                 SyntaxKind::SlashToken,
                 pos,
@@ -158,15 +532,44 @@ impl<'s> Iterator for Lexer<'s> {
                 None,
                 self.span(),
             )),
-            (pos, '=') if matches!(self.source.peek(), Some((_, '='))) => {
-                self.advance();
-                Some(Token::new(
-                    SyntaxKind::EqualsEqualsToken,
-                    pos,
-                    "==".into(),
-                    None,
-                    self.span(),
-                ))
+            (pos, '{') => Some(Token::new(
+                SyntaxKind::LeftBraceToken,
+                pos,
+                String::from("{"),
+                None,
+                self.span(),
+            )),
+            (pos, '}') => Some(Token::new(
+                SyntaxKind::RightBraceToken,
+                pos,
+                String::from("}"),
+                None,
+                self.span(),
+            )),
+            (pos, ',') => Some(Token::new(
+                SyntaxKind::CommaToken,
+                pos,
+                String::from(","),
+                None,
+                self.span(),
+            )),
+            (pos, ':') => Some(Token::new(
+                SyntaxKind::ColonToken,
+                pos,
+                String::from(":"),
+                None,
+                self.span(),
+            )),
+            (pos, '=') => {
+                let (kind, literal) = match self.peek_char() {
+                    Some('=') => (SyntaxKind::EqualsEqualsToken, "=="),
+                    Some('>') => (SyntaxKind::EqualsGreaterToken, "=>"),
+                    _ => (SyntaxKind::EqualsToken, "="),
+                };
+                if literal.len() == 2 {
+                    self.advance();
+                }
+                Some(Token::new(kind, pos, literal.into(), None, self.span()))
             }
             (pos, '!') if matches!(self.source.peek(), Some((_, '='))) => {
                 self.advance();
@@ -178,22 +581,33 @@ impl<'s> Iterator for Lexer<'s> {
                     self.span(),
                 ))
             }
+            (pos, '!') => Some(Token::new(
+                SyntaxKind::BangToken,
+                pos,
+                String::from("!"),
+                None,
+                self.span(),
+            )),
             (pos, '<') => {
-                let (kind, literal) = if let Some((_, '=')) = self.source.peek() {
-                    self.advance();
-                    (SyntaxKind::LessEqualsToken, "<=")
-                } else {
-                    (SyntaxKind::LessToken, "<")
+                let (kind, literal) = match self.peek_char() {
+                    Some('=') => (SyntaxKind::LessEqualsToken, "<="),
+                    Some('<') => (SyntaxKind::LessLessToken, "<<"),
+                    _ => (SyntaxKind::LessToken, "<"),
                 };
+                if literal.len() == 2 {
+                    self.advance();
+                }
                 Some(Token::new(kind, pos, literal.into(), None, self.span()))
             }
             (pos, '>') => {
-                let (kind, literal) = if let Some((_, '=')) = self.source.peek() {
-                    self.advance();
-                    (SyntaxKind::GreaterEqualsToken, ">=")
-                } else {
-                    (SyntaxKind::GreaterToken, ">")
+                let (kind, literal) = match self.peek_char() {
+                    Some('=') => (SyntaxKind::GreaterEqualsToken, ">="),
+                    Some('>') => (SyntaxKind::GreaterGreaterToken, ">>"),
+                    _ => (SyntaxKind::GreaterToken, ">"),
                 };
+                if literal.len() == 2 {
+                    self.advance();
+                }
                 Some(Token::new(kind, pos, literal.into(), None, self.span()))
             }
             (pos, '&') if matches!(self.source.peek(), Some((_, '&'))) => {
@@ -206,14 +620,114 @@ impl<'s> Iterator for Lexer<'s> {
                     self.span(),
                 ))
             }
-            (pos, c) => Some(Token::new(
-                SyntaxKind::BadToken,
+            (pos, '|') if matches!(self.source.peek(), Some((_, '|'))) => {
+                self.advance();
+                Some(Token::new(
+                    SyntaxKind::PipePipeToken,
+                    pos,
+                    "||".into(),
+                    None,
+                    self.span(),
+                ))
+            }
+            (pos, '^') if matches!(self.source.peek(), Some((_, '^'))) => {
+                self.advance();
+                Some(Token::new(
+                    SyntaxKind::HatHatToken,
+                    pos,
+                    "^^".into(),
+                    None,
+                    self.span(),
+                ))
+            }
+            (pos, '&') => Some(Token::new(
+                SyntaxKind::AmpersandToken,
+                pos,
+                String::from("&"),
+                None,
+                self.span(),
+            )),
+            (pos, '|') => Some(Token::new(
+                SyntaxKind::PipeToken,
+                pos,
+                String::from("|"),
+                None,
+                self.span(),
+            )),
+            (pos, '^') => Some(Token::new(
+                SyntaxKind::HatToken,
+                pos,
+                String::from("^"),
+                None,
+                self.span(),
+            )),
+            (pos, '~') => Some(Token::new(
+                SyntaxKind::TildeToken,
                 pos,
-                c.to_string(),
+                String::from("~"),
                 None,
                 self.span(),
             )),
+            (pos, c) => {
+                self.diagnostics.report(Diagnostic::error(
+                    DiagnosticCode::BadCharacter,
+                    self.span(),
+                    format!("bad character in input: {:?}", c),
+                ));
+                Some(Token::new(
+                    SyntaxKind::BadToken,
+                    pos,
+                    c.to_string(),
+                    None,
+                    self.span(),
+                ))
+            }
+        }
+    }
+}
+
+impl<'s> Iterator for Lexer<'s> {
+    type Item = Token;
+
+    /// Produces the next token with the trivia around it attached. Trailing
+    /// trivia runs up to and including the next line break; everything after
+    /// that belongs to the following token's leading trivia.
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut leading_trivia = vec![];
+        let mut token = loop {
+            let token = self.next_raw()?;
+            if !token.kind.is_trivia() {
+                break token;
+            }
+            leading_trivia.push(Trivia::from(token));
+        };
+
+        let mut trailing_trivia = vec![];
+        while self.peek_raw().is_some_and(|t| t.kind.is_trivia()) {
+            let trivia = Trivia::from(self.next_raw().unwrap());
+            let ends_line = trivia.text.contains('\n');
+            trailing_trivia.push(trivia);
+            if ends_line {
+                break;
+            }
+        }
+
+        token.leading_trivia = leading_trivia;
+        token.trailing_trivia = trailing_trivia;
+        Some(token)
+    }
+}
+
+impl<'s> Lexer<'s> {
+    fn next_raw(&mut self) -> Option<Token> {
+        self.lookahead.take().or_else(|| self.lex_token())
+    }
+
+    fn peek_raw(&mut self) -> Option<&Token> {
+        if self.lookahead.is_none() {
+            self.lookahead = self.lex_token();
         }
+        self.lookahead.as_ref()
     }
 }
 
@@ -222,12 +736,14 @@ mod tests {
     use expect_test::expect;
     use expect_test::Expect;
 
+    use crate::syntax::SyntaxKind;
     use crate::Lexer;
     use itertools::join;
 
     fn check_single_token(input: &str, expected: Expect) {
         let mut l = Lexer::new(input);
         let tok = l.next().unwrap();
+        assert_eq!(l.next().unwrap().kind, SyntaxKind::EndOfFileToken);
         assert!(l.next().is_none());
         expected.assert_eq(&format!("{:#?}", tok));
     }
@@ -248,10 +764,122 @@ mod tests {
                         last_line: 1,
                         last_column: 3,
                     },
+                    leading_trivia: [],
+                    trailing_trivia: [],
                 }"#]],
         )
     }
 
+    #[test]
+    fn lex_equals() {
+        check_single_token(
+            "=",
+            expect![[r#"
+            Token {
+                kind: EqualsToken,
+                position: 0,
+                text: "=",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_bang() {
+        check_single_token(
+            "!",
+            expect![[r#"
+            Token {
+                kind: BangToken,
+                position: 0,
+                text: "!",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_bang_equals() {
+        check_single_token(
+            "!=",
+            expect![[r#"
+            Token {
+                kind: BangEqualsToken,
+                position: 0,
+                text: "!=",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 3,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_pipe_pipe() {
+        check_single_token(
+            "||",
+            expect![[r#"
+            Token {
+                kind: PipePipeToken,
+                position: 0,
+                text: "||",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 3,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_hat_hat() {
+        check_single_token(
+            "^^",
+            expect![[r#"
+            Token {
+                kind: HatHatToken,
+                position: 0,
+                text: "^^",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 3,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
     #[test]
     fn lex_bad_token() {
         check_single_token(
@@ -264,58 +892,554 @@ mod tests {
                 value: None,
                 span: Span {
                     first_line: 1,
-                    first_column: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_number() {
+        check_single_token(
+            "123",
+            expect![[r#"
+            Token {
+                kind: NumberToken,
+                position: 0,
+                text: "123",
+                value: Some(
+                    Int(
+                        123,
+                    ),
+                ),
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 4,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_float() {
+        check_single_token(
+            "3.14",
+            expect![[r#"
+            Token {
+                kind: NumberToken,
+                position: 0,
+                text: "3.14",
+                value: Some(
+                    Float(
+                        3.14,
+                    ),
+                ),
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 5,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_hexadecimal() {
+        check_single_token(
+            "0xFF",
+            expect![[r#"
+            Token {
+                kind: NumberToken,
+                position: 0,
+                text: "0xFF",
+                value: Some(
+                    Int(
+                        255,
+                    ),
+                ),
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 5,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_number_formats() {
+        check_numbers(
+            &[
+                "1_000_000",
+                "0.5",
+                "1e-9",
+                "2.5E+3",
+                "1e10",
+                "0o17",
+                "0b1010",
+                "0B1111_0000",
+                "0XdeadBEEF",
+                "9223372036854775807",
+            ],
+            expect![[r#"
+                1_000_000 => 1000000
+                0.5 => 0.5
+                1e-9 => 1e-9
+                2.5E+3 => 2500.0
+                1e10 => 10000000000.0
+                0o17 => 15
+                0b1010 => 10
+                0B1111_0000 => 240
+                0XdeadBEEF => 3735928559
+                9223372036854775807 => 9223372036854775807"#]],
+        )
+    }
+
+    #[test]
+    fn lex_malformed_numbers() {
+        check_numbers(
+            &[
+                "1e",
+                "1e+",
+                "0x",
+                "0b_",
+                "0b102",
+                "0o8",
+                "12abc",
+                "1e999",
+                "9223372036854775808",
+                "0x8000000000000000",
+            ],
+            expect![[r#"
+                1e => error[DB0007] at 1:1: expected digits in the exponent of number literal
+                1e+ => error[DB0007] at 1:1: expected digits in the exponent of number literal
+                0x => error[DB0007] at 1:1: expected digits after `0x`
+                0b_ => error[DB0007] at 1:1: expected digits after `0b`
+                0b102 => error[DB0007] at 1:1: invalid digit `2` in base 2 number literal
+                0o8 => error[DB0007] at 1:1: invalid digit `8` in base 8 number literal
+                12abc => error[DB0007] at 1:1: invalid suffix `abc` on number literal
+                1e999 => error[DB0007] at 1:1: float literal is too large
+                9223372036854775808 => error[DB0007] at 1:1: integer literal is too large
+                0x8000000000000000 => error[DB0007] at 1:1: integer literal is too large"#]],
+        )
+    }
+
+    #[test]
+    fn dot_without_digits_is_not_part_of_number() {
+        check_tokens(
+            "1.",
+            expect![[r#"
+            Token {
+                kind: NumberToken,
+                position: 0,
+                text: "1",
+                value: Some(
+                    Int(
+                        1,
+                    ),
+                ),
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }
+            Token {
+                kind: BadToken,
+                position: 1,
+                text: ".",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 2,
+                    last_line: 1,
+                    last_column: 3,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }
+            Token {
+                kind: EndOfFileToken,
+                position: 2,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 3,
+                    last_line: 1,
+                    last_column: 3,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_string() {
+        check_single_token(
+            r#""hi there""#,
+            expect![[r#"
+            Token {
+                kind: StringToken,
+                position: 0,
+                text: "\"hi there\"",
+                value: Some(
+                    String(
+                        "hi there",
+                    ),
+                ),
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 11,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_string_escapes() {
+        check_strings(
+            &[
+                r#""a\nb""#,
+                r#""tab\there""#,
+                r#""say \"hi\"""#,
+                r#""back\\slash""#,
+                r#""\u{48}\u{1F600}""#,
+                r#""""#,
+            ],
+            expect![[r#"
+                "a\nb" => "a\nb"
+                "tab\there" => "tab\there"
+                "say \"hi\"" => "say \"hi\""
+                "back\\slash" => "back\\slash"
+                "\u{48}\u{1F600}" => "H😀"
+                "" => """#]],
+        )
+    }
+
+    #[test]
+    fn lex_malformed_strings() {
+        check_strings(
+            &[
+                r#""open"#,
+                r#""\q""#,
+                r#""\u{110000}""#,
+                r#""\u{}""#,
+                r#""\u41""#,
+                r#""ends in \"#,
+            ],
+            expect![[r#"
+                "open => error[DB0008] at 1:1: unterminated string literal
+                "\q" => error[DB0009] at 1:2: unknown escape sequence `\q`
+                "\u{110000}" => error[DB0009] at 1:2: invalid unicode escape, expected `\u{...}` with 1 to 6 hex digits naming a character
+                "\u{}" => error[DB0009] at 1:2: invalid unicode escape, expected `\u{...}` with 1 to 6 hex digits naming a character
+                "\u41" => error[DB0009] at 1:2: invalid unicode escape, expected `\u{...}` with 1 to 6 hex digits naming a character
+                "ends in \ => error[DB0008] at 1:1: unterminated string literal"#]],
+        )
+    }
+
+    #[test]
+    fn string_ends_at_newline() {
+        check_tokens(
+            "\"abc\n1",
+            expect![[r#"
+            Token {
+                kind: StringToken,
+                position: 0,
+                text: "\"abc",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 5,
+                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 4,
+                        text: "\n",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 5,
+                            last_line: 2,
+                            last_column: 1,
+                        },
+                    },
+                ],
+            }
+            Token {
+                kind: NumberToken,
+                position: 5,
+                text: "1",
+                value: Some(
+                    Int(
+                        1,
+                    ),
+                ),
+                span: Span {
+                    first_line: 2,
+                    first_column: 1,
+                    last_line: 2,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }
+            Token {
+                kind: EndOfFileToken,
+                position: 6,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 2,
+                    first_column: 2,
+                    last_line: 2,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_whitespace() {
+        check_tokens(
+            "  \t ",
+            expect![[r#"
+            Token {
+                kind: EndOfFileToken,
+                position: 4,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 5,
                     last_line: 1,
-                    last_column: 2,
+                    last_column: 5,
                 },
+                leading_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 0,
+                        text: "  \t ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 1,
+                            last_line: 1,
+                            last_column: 5,
+                        },
+                    },
+                ],
+                trailing_trivia: [],
             }"#]],
         )
     }
 
     #[test]
-    fn lex_number() {
-        check_single_token(
-            "123",
+    fn trailing_trivia_stops_at_line_break() {
+        check_tokens(
+            "1 // one\n  2",
             expect![[r#"
             Token {
                 kind: NumberToken,
                 position: 0,
-                text: "123",
+                text: "1",
                 value: Some(
-                    Number(
-                        123.0,
+                    Int(
+                        1,
                     ),
                 ),
                 span: Span {
                     first_line: 1,
                     first_column: 1,
                     last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 1,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 2,
+                            last_line: 1,
+                            last_column: 3,
+                        },
+                    },
+                    Trivia {
+                        kind: LineCommentTrivia,
+                        position: 2,
+                        text: "// one",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 3,
+                            last_line: 1,
+                            last_column: 9,
+                        },
+                    },
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 8,
+                        text: "\n",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 9,
+                            last_line: 2,
+                            last_column: 1,
+                        },
+                    },
+                ],
+            }
+            Token {
+                kind: NumberToken,
+                position: 11,
+                text: "2",
+                value: Some(
+                    Int(
+                        2,
+                    ),
+                ),
+                span: Span {
+                    first_line: 2,
+                    first_column: 3,
+                    last_line: 2,
+                    last_column: 4,
+                },
+                leading_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 9,
+                        text: "  ",
+                        span: Span {
+                            first_line: 2,
+                            first_column: 1,
+                            last_line: 2,
+                            last_column: 3,
+                        },
+                    },
+                ],
+                trailing_trivia: [],
+            }
+            Token {
+                kind: EndOfFileToken,
+                position: 12,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 2,
+                    first_column: 4,
+                    last_line: 2,
                     last_column: 4,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
 
     #[test]
-    fn lex_whitespace() {
-        check_single_token(
-            "  \t ",
+    fn lex_nested_block_comment() {
+        check_tokens(
+            "/* a /* b */ c */+",
             expect![[r#"
             Token {
-                kind: WhitespaceToken,
-                position: 0,
-                text: "  \t ",
+                kind: PlusToken,
+                position: 17,
+                text: "+",
                 value: None,
                 span: Span {
                     first_line: 1,
-                    first_column: 1,
+                    first_column: 18,
                     last_line: 1,
-                    last_column: 5,
+                    last_column: 19,
+                },
+                leading_trivia: [
+                    Trivia {
+                        kind: BlockCommentTrivia,
+                        position: 0,
+                        text: "/* a /* b */ c */",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 1,
+                            last_line: 1,
+                            last_column: 18,
+                        },
+                    },
+                ],
+                trailing_trivia: [],
+            }
+            Token {
+                kind: EndOfFileToken,
+                position: 18,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 19,
+                    last_line: 1,
+                    last_column: 19,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
 
+    #[test]
+    fn report_unterminated_block_comment() {
+        let mut l = Lexer::new("1 /* a /* b */");
+        while l.next().is_some() {}
+        let diagnostics = l
+            .diagnostics()
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>();
+        expect!["error[DB0010] at 1:3: unterminated block comment"]
+            .assert_eq(&join(diagnostics, "\n"));
+    }
+
+    #[test]
+    fn tokens_reproduce_source() {
+        let inputs = [
+            "",
+            "  1 +\t2  ",
+            "// only a comment",
+            "1 // trailing\n\n  /* leading */ 2 /* unterminated",
+            "/*/ still a comment */ @ \"str\\n\" \r\n0x1F",
+        ];
+        for input in inputs {
+            let text: String = Lexer::new(input).map(|t| t.full_text()).collect();
+            assert_eq!(text, input);
+        }
+    }
+
     #[test]
     fn lex_plus() {
         check_single_token(
@@ -332,6 +1456,8 @@ mod tests {
                     last_line: 1,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
@@ -352,6 +1478,8 @@ mod tests {
                     last_line: 1,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
@@ -372,6 +1500,8 @@ mod tests {
                     last_line: 1,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
@@ -392,6 +1522,8 @@ mod tests {
                     last_line: 1,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
@@ -412,6 +1544,8 @@ mod tests {
                     last_line: 1,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
@@ -432,10 +1566,261 @@ mod tests {
                     last_line: 1,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_left_brace() {
+        check_single_token(
+            "{",
+            expect![[r#"
+            Token {
+                kind: LeftBraceToken,
+                position: 0,
+                text: "{",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_right_brace() {
+        check_single_token(
+            "}",
+            expect![[r#"
+            Token {
+                kind: RightBraceToken,
+                position: 0,
+                text: "}",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_declaration_keywords() {
+        let kinds = Lexer::new("let var lettuce")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::LetKeyword,
+                SyntaxKind::VarKeyword,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::EndOfFileToken,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_comma() {
+        check_single_token(
+            ",",
+            expect![[r#"
+            Token {
+                kind: CommaToken,
+                position: 0,
+                text: ",",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn lex_colon() {
+        check_single_token(
+            ":",
+            expect![[r#"
+            Token {
+                kind: ColonToken,
+                position: 0,
+                text: ":",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 1,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"#]],
         )
     }
 
+    #[test]
+    fn lex_function_keywords() {
+        let kinds = Lexer::new("function return functions")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::FunctionKeyword,
+                SyntaxKind::ReturnKeyword,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::EndOfFileToken,
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_text_lexes_back_to_its_kind() {
+        let kinds = [
+            SyntaxKind::PlusToken,
+            SyntaxKind::StarToken,
+            SyntaxKind::StarStarToken,
+            SyntaxKind::PercentToken,
+            SyntaxKind::TildeSlashToken,
+            SyntaxKind::LeftBraceToken,
+            SyntaxKind::ColonToken,
+            SyntaxKind::EqualsToken,
+            SyntaxKind::BangEqualsToken,
+            SyntaxKind::LessEqualsToken,
+            SyntaxKind::HatHatToken,
+            SyntaxKind::AmpersandToken,
+            SyntaxKind::PipeToken,
+            SyntaxKind::HatToken,
+            SyntaxKind::TildeToken,
+            SyntaxKind::LessLessToken,
+            SyntaxKind::GreaterGreaterToken,
+            SyntaxKind::PlusEqualsToken,
+            SyntaxKind::SlashEqualsToken,
+            SyntaxKind::MinusMinusToken,
+            SyntaxKind::EqualsGreaterToken,
+            SyntaxKind::FalseKeyword,
+            SyntaxKind::ContinueKeyword,
+            SyntaxKind::ReturnKeyword,
+        ];
+        for kind in kinds.iter().copied() {
+            let text = kind.text().unwrap();
+            assert_eq!(Lexer::new(text).next().unwrap().kind, kind, "{}", text);
+        }
+        assert_eq!(SyntaxKind::IdentifierToken.text(), None);
+    }
+
+    #[test]
+    fn single_and_double_operator_characters_are_distinguished() {
+        let kinds = Lexer::new("& && | || ^ ^^ ~ ~/ < << <= > >> >=")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::AmpersandToken,
+                SyntaxKind::AmpersandAmpersandToken,
+                SyntaxKind::PipeToken,
+                SyntaxKind::PipePipeToken,
+                SyntaxKind::HatToken,
+                SyntaxKind::HatHatToken,
+                SyntaxKind::TildeToken,
+                SyntaxKind::TildeSlashToken,
+                SyntaxKind::LessToken,
+                SyntaxKind::LessLessToken,
+                SyntaxKind::LessEqualsToken,
+                SyntaxKind::GreaterToken,
+                SyntaxKind::GreaterGreaterToken,
+                SyntaxKind::GreaterEqualsToken,
+                SyntaxKind::EndOfFileToken,
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_after_a_tilde_are_not_floor_division() {
+        let kinds = Lexer::new("~/*c*/x ~/ y ~// c")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::TildeToken,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::TildeSlashToken,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::TildeToken,
+                SyntaxKind::EndOfFileToken,
+            ]
+        );
+    }
+
+    #[test]
+    fn assignment_operators_are_single_tokens() {
+        let kinds = Lexer::new("+ += ++ - -= -- * *= ** / /= x+++y")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::PlusToken,
+                SyntaxKind::PlusEqualsToken,
+                SyntaxKind::PlusPlusToken,
+                SyntaxKind::MinusToken,
+                SyntaxKind::MinusEqualsToken,
+                SyntaxKind::MinusMinusToken,
+                SyntaxKind::StarToken,
+                SyntaxKind::StarEqualsToken,
+                SyntaxKind::StarStarToken,
+                SyntaxKind::SlashToken,
+                SyntaxKind::SlashEqualsToken,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::PlusPlusToken,
+                SyntaxKind::PlusToken,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::EndOfFileToken,
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_control_flow_keywords() {
+        let kinds = Lexer::new("if else while for to break continue iffy")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::IfKeyword,
+                SyntaxKind::ElseKeyword,
+                SyntaxKind::WhileKeyword,
+                SyntaxKind::ForKeyword,
+                SyntaxKind::ToKeyword,
+                SyntaxKind::BreakKeyword,
+                SyntaxKind::ContinueKeyword,
+                SyntaxKind::IdentifierToken,
+                SyntaxKind::EndOfFileToken,
+            ]
+        );
+    }
+
     #[test]
     fn lex_lots_of_things() {
         check_tokens(
@@ -446,8 +1831,8 @@ mod tests {
                 position: 0,
                 text: "123",
                 value: Some(
-                    Number(
-                        123.0,
+                    Int(
+                        123,
                     ),
                 ),
                 span: Span {
@@ -456,18 +1841,20 @@ mod tests {
                     last_line: 1,
                     last_column: 4,
                 },
-            }
-            Token {
-                kind: WhitespaceToken,
-                position: 3,
-                text: " ",
-                value: None,
-                span: Span {
-                    first_line: 1,
-                    first_column: 4,
-                    last_line: 1,
-                    last_column: 5,
-                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 3,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 4,
+                            last_line: 1,
+                            last_column: 5,
+                        },
+                    },
+                ],
             }
             Token {
                 kind: PlusToken,
@@ -480,26 +1867,28 @@ mod tests {
                     last_line: 1,
                     last_column: 6,
                 },
-            }
-            Token {
-                kind: WhitespaceToken,
-                position: 5,
-                text: " ",
-                value: None,
-                span: Span {
-                    first_line: 1,
-                    first_column: 6,
-                    last_line: 1,
-                    last_column: 7,
-                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 5,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 6,
+                            last_line: 1,
+                            last_column: 7,
+                        },
+                    },
+                ],
             }
             Token {
                 kind: NumberToken,
                 position: 6,
                 text: "456",
                 value: Some(
-                    Number(
-                        456.0,
+                    Int(
+                        456,
                     ),
                 ),
                 span: Span {
@@ -508,18 +1897,20 @@ mod tests {
                     last_line: 1,
                     last_column: 10,
                 },
-            }
-            Token {
-                kind: WhitespaceToken,
-                position: 9,
-                text: " ",
-                value: None,
-                span: Span {
-                    first_line: 1,
-                    first_column: 10,
-                    last_line: 1,
-                    last_column: 11,
-                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 9,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 10,
+                            last_line: 1,
+                            last_column: 11,
+                        },
+                    },
+                ],
             }
             Token {
                 kind: MinusToken,
@@ -532,18 +1923,20 @@ mod tests {
                     last_line: 1,
                     last_column: 12,
                 },
-            }
-            Token {
-                kind: WhitespaceToken,
-                position: 11,
-                text: " ",
-                value: None,
-                span: Span {
-                    first_line: 1,
-                    first_column: 12,
-                    last_line: 1,
-                    last_column: 13,
-                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 11,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 12,
+                            last_line: 1,
+                            last_column: 13,
+                        },
+                    },
+                ],
             }
             Token {
                 kind: BadToken,
@@ -556,6 +1949,8 @@ mod tests {
                     last_line: 1,
                     last_column: 14,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: BadToken,
@@ -568,6 +1963,8 @@ mod tests {
                     last_line: 1,
                     last_column: 15,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: BadToken,
@@ -580,18 +1977,20 @@ mod tests {
                     last_line: 1,
                     last_column: 16,
                 },
-            }
-            Token {
-                kind: WhitespaceToken,
-                position: 15,
-                text: " ",
-                value: None,
-                span: Span {
-                    first_line: 1,
-                    first_column: 16,
-                    last_line: 1,
-                    last_column: 17,
-                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 15,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 16,
+                            last_line: 1,
+                            last_column: 17,
+                        },
+                    },
+                ],
             }
             Token {
                 kind: StarToken,
@@ -604,26 +2003,28 @@ mod tests {
                     last_line: 1,
                     last_column: 18,
                 },
-            }
-            Token {
-                kind: WhitespaceToken,
-                position: 17,
-                text: "\n",
-                value: None,
-                span: Span {
-                    first_line: 1,
-                    first_column: 18,
-                    last_line: 2,
-                    last_column: 1,
-                },
+                leading_trivia: [],
+                trailing_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 17,
+                        text: "\n",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 18,
+                            last_line: 2,
+                            last_column: 1,
+                        },
+                    },
+                ],
             }
             Token {
                 kind: NumberToken,
                 position: 18,
                 text: "5",
                 value: Some(
-                    Number(
-                        5.0,
+                    Int(
+                        5,
                     ),
                 ),
                 span: Span {
@@ -632,6 +2033,8 @@ mod tests {
                     last_line: 2,
                     last_column: 2,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: PlusToken,
@@ -644,14 +2047,16 @@ mod tests {
                     last_line: 2,
                     last_column: 3,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: NumberToken,
                 position: 20,
                 text: "2",
                 value: Some(
-                    Number(
-                        2.0,
+                    Int(
+                        2,
                     ),
                 ),
                 span: Span {
@@ -660,6 +2065,8 @@ mod tests {
                     last_line: 2,
                     last_column: 4,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: PlusToken,
@@ -672,14 +2079,16 @@ mod tests {
                     last_line: 2,
                     last_column: 5,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: NumberToken,
                 position: 22,
                 text: "3",
                 value: Some(
-                    Number(
-                        3.0,
+                    Int(
+                        3,
                     ),
                 ),
                 span: Span {
@@ -688,6 +2097,8 @@ mod tests {
                     last_line: 2,
                     last_column: 6,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: SlashToken,
@@ -700,14 +2111,16 @@ mod tests {
                     last_line: 2,
                     last_column: 7,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: NumberToken,
                 position: 24,
                 text: "4",
                 value: Some(
-                    Number(
-                        4.0,
+                    Int(
+                        4,
                     ),
                 ),
                 span: Span {
@@ -716,6 +2129,8 @@ mod tests {
                     last_line: 2,
                     last_column: 8,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: LeftParenthesisToken,
@@ -728,6 +2143,8 @@ mod tests {
                     last_line: 2,
                     last_column: 9,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: RightParenthesisToken,
@@ -740,6 +2157,8 @@ mod tests {
                     last_line: 2,
                     last_column: 10,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
             }
             Token {
                 kind: BadToken,
@@ -752,10 +2171,115 @@ mod tests {
                     last_line: 2,
                     last_column: 11,
                 },
+                leading_trivia: [],
+                trailing_trivia: [],
+            }
+            Token {
+                kind: EndOfFileToken,
+                position: 28,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 2,
+                    first_column: 11,
+                    last_line: 2,
+                    last_column: 11,
+                },
+                leading_trivia: [],
+                trailing_trivia: [],
             }"##]],
         )
     }
 
+    #[test]
+    fn lex_end_of_file() {
+        check_tokens(
+            " ",
+            expect![[r#"
+            Token {
+                kind: EndOfFileToken,
+                position: 1,
+                text: "",
+                value: None,
+                span: Span {
+                    first_line: 1,
+                    first_column: 2,
+                    last_line: 1,
+                    last_column: 2,
+                },
+                leading_trivia: [
+                    Trivia {
+                        kind: WhitespaceTrivia,
+                        position: 0,
+                        text: " ",
+                        span: Span {
+                            first_line: 1,
+                            first_column: 1,
+                            last_line: 1,
+                            last_column: 2,
+                        },
+                    },
+                ],
+                trailing_trivia: [],
+            }"#]],
+        )
+    }
+
+    #[test]
+    fn bad_token_is_reported() {
+        let mut l = Lexer::new("1 @");
+        while l.next().is_some() {}
+        let diagnostics = l
+            .diagnostics()
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>();
+        expect!["error[DB0001] at 1:3: bad character in input: '@'"]
+            .assert_eq(&join(diagnostics, "\n"));
+    }
+
+    /// Lexes each input as a single number and prints its value, or the
+    /// diagnostics it produced.
+    fn check_numbers(inputs: &[&str], expected: Expect) {
+        let mut lines = vec![];
+        for input in inputs {
+            let mut l = Lexer::new(input);
+            let tok = l.next().unwrap();
+            assert_eq!(tok.kind, SyntaxKind::NumberToken);
+            assert_eq!(&tok.text, input);
+            match tok.value {
+                Some(value) => lines.push(format!("{} => {}", input, value)),
+                None => {
+                    for d in l.diagnostics() {
+                        lines.push(format!("{} => {}", input, d));
+                    }
+                }
+            }
+        }
+        expected.assert_eq(&join(lines, "\n"));
+    }
+
+    /// Lexes each input as a single string and prints its value, or the
+    /// diagnostics it produced.
+    fn check_strings(inputs: &[&str], expected: Expect) {
+        let mut lines = vec![];
+        for input in inputs {
+            let mut l = Lexer::new(input);
+            let tok = l.next().unwrap();
+            assert_eq!(tok.kind, SyntaxKind::StringToken);
+            assert_eq!(&tok.text, input);
+            match tok.value {
+                Some(value) => lines.push(format!("{} => {}", input, value)),
+                None => {
+                    for d in l.diagnostics() {
+                        lines.push(format!("{} => {}", input, d));
+                    }
+                }
+            }
+        }
+        expected.assert_eq(&join(lines, "\n"));
+    }
+
     fn check_tokens(input: &str, expected: Expect) {
         let l = Lexer::new(input);
         let mut str_repr = vec![];
@@ -763,6 +2287,6 @@ mod tests {
             str_repr.push(format!("{:#?}", tok));
         }
 
-        expected.assert_eq(&join(str_repr.into_iter(), "\n"));
+        expected.assert_eq(&join(str_repr, "\n"));
     }
 }
@@ -1,14 +1,23 @@
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum SyntaxKind {
     NumberToken,
-    WhitespaceToken,
+    StringToken,
     PlusToken,
     MinusToken,
     StarToken,
     SlashToken,
+    StarStarToken,
+    PercentToken,
+    TildeSlashToken,
     LeftParenthesisToken,
     RightParenthesisToken,
+    LeftBraceToken,
+    RightBraceToken,
+    CommaToken,
+    ColonToken,
+    EqualsToken,
     EqualsEqualsToken,
+    BangToken,
     BangEqualsToken,
     LessToken,
     LessEqualsToken,
@@ -16,45 +25,208 @@ pub enum SyntaxKind {
     GreaterEqualsToken,
     AmpersandAmpersandToken,
     PipePipeToken,
+    HatHatToken,
+    AmpersandToken,
+    PipeToken,
+    HatToken,
+    TildeToken,
+    LessLessToken,
+    GreaterGreaterToken,
+    PlusEqualsToken,
+    MinusEqualsToken,
+    StarEqualsToken,
+    SlashEqualsToken,
+    PlusPlusToken,
+    MinusMinusToken,
+    EqualsGreaterToken,
 
     IdentifierToken,
 
     TrueKeyword,
     FalseKeyword,
+    LetKeyword,
+    VarKeyword,
+    IfKeyword,
+    ElseKeyword,
+    WhileKeyword,
+    ForKeyword,
+    ToKeyword,
+    BreakKeyword,
+    ContinueKeyword,
+    FunctionKeyword,
+    ReturnKeyword,
 
     BadToken,
+    EndOfFileToken,
+
+    WhitespaceTrivia,
+    LineCommentTrivia,
+    BlockCommentTrivia,
+    SkippedTokensTrivia,
 
     LiteralExpression,
     BinaryExpression,
     UnaryExpression,
     ParenthesizedExpression,
+    NameExpression,
+    AssignmentExpression,
+    CompoundAssignmentExpression,
+    PrefixIncrementExpression,
+    PostfixIncrementExpression,
+    LambdaExpression,
+    CallExpression,
+    ErrorExpression,
+
+    BlockStatement,
+    ExpressionStatement,
+    VariableDeclaration,
+    IfStatement,
+    ElseClause,
+    WhileStatement,
+    ForStatement,
+    BreakStatement,
+    ContinueStatement,
+    ReturnStatement,
+
+    FunctionDeclaration,
+    Parameter,
+    TypeClause,
+    GlobalStatement,
+
+    CompilationUnit,
 }
 
 pub(crate) fn check_keyword(lexeme: &str) -> SyntaxKind {
     match lexeme {
         "true" => SyntaxKind::TrueKeyword,
         "false" => SyntaxKind::FalseKeyword,
+        "let" => SyntaxKind::LetKeyword,
+        "var" => SyntaxKind::VarKeyword,
+        "if" => SyntaxKind::IfKeyword,
+        "else" => SyntaxKind::ElseKeyword,
+        "while" => SyntaxKind::WhileKeyword,
+        "for" => SyntaxKind::ForKeyword,
+        "to" => SyntaxKind::ToKeyword,
+        "break" => SyntaxKind::BreakKeyword,
+        "continue" => SyntaxKind::ContinueKeyword,
+        "function" => SyntaxKind::FunctionKeyword,
+        "return" => SyntaxKind::ReturnKeyword,
         _ => SyntaxKind::IdentifierToken,
     }
 }
 impl SyntaxKind {
+    pub fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            Self::WhitespaceTrivia
+                | Self::LineCommentTrivia
+                | Self::BlockCommentTrivia
+                | Self::SkippedTokensTrivia
+        )
+    }
+
     pub fn binary_precedence(self) -> usize {
         match self {
-            Self::PlusToken | Self::MinusToken => 4,
-            Self::StarToken | Self::SlashToken => 5,
-            Self::EqualsEqualsToken | Self::BangEqualsToken => 1,
-            Self::AmpersandAmpersandToken | Self::PipePipeToken => 2,
+            Self::StarStarToken => 13,
+            Self::StarToken | Self::SlashToken | Self::PercentToken | Self::TildeSlashToken => 11,
+            Self::PlusToken | Self::MinusToken => 10,
+            Self::LessLessToken | Self::GreaterGreaterToken => 9,
             Self::LessToken
             | Self::LessEqualsToken
             | Self::GreaterToken
-            | Self::GreaterEqualsToken => 3,
+            | Self::GreaterEqualsToken => 8,
+            Self::EqualsEqualsToken | Self::BangEqualsToken => 7,
+            // Bitwise operators bind looser than comparisons, as in C.
+            Self::AmpersandToken => 6,
+            Self::HatToken => 5,
+            Self::PipeToken => 4,
+            Self::AmpersandAmpersandToken => 3,
+            Self::HatHatToken => 2,
+            Self::PipePipeToken => 1,
             _ => 0,
         }
     }
 
+    /// The fixed text of an operator or keyword, or `None` for tokens whose
+    /// text varies.
+    pub fn text(self) -> Option<&'static str> {
+        Some(match self {
+            Self::PlusToken => "+",
+            Self::MinusToken => "-",
+            Self::StarToken => "*",
+            Self::SlashToken => "/",
+            Self::StarStarToken => "**",
+            Self::PercentToken => "%",
+            Self::TildeSlashToken => "~/",
+            Self::LeftParenthesisToken => "(",
+            Self::RightParenthesisToken => ")",
+            Self::LeftBraceToken => "{",
+            Self::RightBraceToken => "}",
+            Self::CommaToken => ",",
+            Self::ColonToken => ":",
+            Self::EqualsToken => "=",
+            Self::EqualsEqualsToken => "==",
+            Self::BangToken => "!",
+            Self::BangEqualsToken => "!=",
+            Self::LessToken => "<",
+            Self::LessEqualsToken => "<=",
+            Self::GreaterToken => ">",
+            Self::GreaterEqualsToken => ">=",
+            Self::AmpersandAmpersandToken => "&&",
+            Self::PipePipeToken => "||",
+            Self::HatHatToken => "^^",
+            Self::AmpersandToken => "&",
+            Self::PipeToken => "|",
+            Self::HatToken => "^",
+            Self::TildeToken => "~",
+            Self::LessLessToken => "<<",
+            Self::GreaterGreaterToken => ">>",
+            Self::PlusEqualsToken => "+=",
+            Self::MinusEqualsToken => "-=",
+            Self::StarEqualsToken => "*=",
+            Self::SlashEqualsToken => "/=",
+            Self::PlusPlusToken => "++",
+            Self::MinusMinusToken => "--",
+            Self::EqualsGreaterToken => "=>",
+            Self::TrueKeyword => "true",
+            Self::FalseKeyword => "false",
+            Self::LetKeyword => "let",
+            Self::VarKeyword => "var",
+            Self::IfKeyword => "if",
+            Self::ElseKeyword => "else",
+            Self::WhileKeyword => "while",
+            Self::ForKeyword => "for",
+            Self::ToKeyword => "to",
+            Self::BreakKeyword => "break",
+            Self::ContinueKeyword => "continue",
+            Self::FunctionKeyword => "function",
+            Self::ReturnKeyword => "return",
+            _ => return None,
+        })
+    }
+
+    /// The binary operator that a compound assignment like `+=`, or an
+    /// increment like `++`, applies to its variable.
+    pub fn assignment_operator(self) -> Option<SyntaxKind> {
+        match self {
+            Self::PlusEqualsToken | Self::PlusPlusToken => Some(Self::PlusToken),
+            Self::MinusEqualsToken | Self::MinusMinusToken => Some(Self::MinusToken),
+            Self::StarEqualsToken => Some(Self::StarToken),
+            Self::SlashEqualsToken => Some(Self::SlashToken),
+            _ => None,
+        }
+    }
+
+    /// Whether `a op b op c` means `a op (b op c)`.
+    pub fn is_right_associative(self) -> bool {
+        self == Self::StarStarToken
+    }
+
+    /// Unary operators bind tighter than all binary operators except `**`,
+    /// so that `-2 ** 2` is `-(2 ** 2)`.
     pub fn unary_precedence(self) -> usize {
         match self {
-            Self::PlusToken | Self::MinusToken => 6,
+            Self::PlusToken | Self::MinusToken | Self::BangToken | Self::TildeToken => 12,
             _ => 0,
         }
     }
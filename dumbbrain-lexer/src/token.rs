@@ -3,6 +3,27 @@ use dumbbrain::object::DumbBrainObject;
 use crate::span::Span;
 use crate::syntax::SyntaxKind;
 
+/// Source text that carries no meaning for the parser, such as whitespace
+/// and comments, kept on the neighbouring token so nothing is lost.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trivia {
+    pub kind: SyntaxKind,
+    pub position: usize,
+    pub text: String,
+    pub span: Span,
+}
+
+impl From<Token> for Trivia {
+    fn from(token: Token) -> Self {
+        Self {
+            kind: token.kind,
+            position: token.position,
+            text: token.text,
+            span: token.span,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub kind: SyntaxKind,
@@ -10,6 +31,8 @@ pub struct Token {
     pub text: String,
     pub value: Option<DumbBrainObject>,
     pub span: Span,
+    pub leading_trivia: Vec<Trivia>,
+    pub trailing_trivia: Vec<Trivia>,
 }
 
 impl Token {
@@ -29,6 +52,28 @@ impl Token {
             text,
             value: value.into(),
             span,
+            leading_trivia: vec![],
+            trailing_trivia: vec![],
+        }
+    }
+
+    /// Creates a zero-width token standing in for one the parser expected but
+    /// did not find in the source.
+    pub fn missing(kind: SyntaxKind, position: usize, span: Span) -> Self {
+        Self::new(kind, position, String::new(), None, span)
+    }
+
+    /// The token's text surrounded by its trivia, exactly as it appeared in
+    /// the source.
+    pub fn full_text(&self) -> String {
+        let mut text = String::new();
+        for trivia in &self.leading_trivia {
+            text.push_str(&trivia.text);
+        }
+        text.push_str(&self.text);
+        for trivia in &self.trailing_trivia {
+            text.push_str(&trivia.text);
         }
+        text
     }
 }
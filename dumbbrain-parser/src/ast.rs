@@ -1,3 +1,4 @@
+use dumbbrain_lexer::span::Span;
 use dumbbrain_lexer::token::Token;
 
 pub mod traits;
@@ -16,9 +17,176 @@ pub enum ExpressionSyntax {
         operator_token: Token,
         right: Box<ExpressionSyntax>,
     },
+    Name {
+        identifier_token: Token,
+    },
+    Assignment {
+        identifier_token: Token,
+        equals_token: Token,
+        expression: Box<ExpressionSyntax>,
+    },
+    /// `x += e`, and likewise for `-=`, `*=` and `/=`.
+    CompoundAssignment {
+        identifier_token: Token,
+        operator_token: Token,
+        expression: Box<ExpressionSyntax>,
+    },
+    /// `++x` or `--x`.
+    PrefixIncrement {
+        operator_token: Token,
+        identifier_token: Token,
+    },
+    /// `x++` or `x--`.
+    PostfixIncrement {
+        identifier_token: Token,
+        operator_token: Token,
+    },
+    /// `(x, y) => x + y`, or with a block as its body. Parameters may leave
+    /// out their types, which are then inferred from the body.
+    Lambda {
+        left_parenthesis_token: Token,
+        parameters: SeparatedSyntaxList<ParameterSyntax>,
+        right_parenthesis_token: Token,
+        arrow_token: Token,
+        body: Box<StatementSyntax>,
+    },
+    Call {
+        identifier_token: Token,
+        left_parenthesis_token: Token,
+        arguments: SeparatedSyntaxList<ExpressionSyntax>,
+        right_parenthesis_token: Token,
+    },
     Parenthesized {
         left_parenthesis_token: Token,
         expression: Box<ExpressionSyntax>,
         right_parenthesis_token: Token,
     },
+    /// Stands in for an expression that could not be parsed. Holds the tokens
+    /// skipped while recovering, which may be none at all, so the span is
+    /// kept separately.
+    Error {
+        tokens: Vec<Token>,
+        span: Span,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementSyntax {
+    Block {
+        left_brace_token: Token,
+        statements: Vec<StatementSyntax>,
+        right_brace_token: Token,
+    },
+    Expression {
+        expression: ExpressionSyntax,
+    },
+    /// `let` declares a read-only variable, `var` a mutable one. Without a
+    /// type clause, the variable has the type of its initializer.
+    VariableDeclaration {
+        keyword_token: Token,
+        identifier_token: Token,
+        type_clause: Option<TypeClauseSyntax>,
+        equals_token: Token,
+        initializer: Box<ExpressionSyntax>,
+    },
+    If {
+        if_keyword: Token,
+        condition: ExpressionSyntax,
+        then_statement: Box<StatementSyntax>,
+        else_clause: Option<ElseClauseSyntax>,
+    },
+    While {
+        while_keyword: Token,
+        condition: ExpressionSyntax,
+        body: Box<StatementSyntax>,
+    },
+    /// `for i = 1 to 10 body` runs `body` once for every value from the
+    /// lower bound up to and including the upper bound.
+    For {
+        for_keyword: Token,
+        identifier_token: Token,
+        equals_token: Token,
+        lower_bound: Box<ExpressionSyntax>,
+        to_keyword: Token,
+        upper_bound: Box<ExpressionSyntax>,
+        body: Box<StatementSyntax>,
+    },
+    Break {
+        break_keyword: Token,
+    },
+    Continue {
+        continue_keyword: Token,
+    },
+    Return {
+        return_keyword: Token,
+        expression: Option<ExpressionSyntax>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElseClauseSyntax {
+    pub else_keyword: Token,
+    pub else_statement: Box<StatementSyntax>,
+}
+
+/// Nodes separated by tokens such as commas. The separators are kept so that
+/// the tree still covers the whole source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeparatedSyntaxList<T> {
+    pub nodes: Vec<T>,
+    pub separators: Vec<Token>,
+}
+
+impl<T> SeparatedSyntaxList<T> {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.nodes.iter()
+    }
+}
+
+/// `: name`, giving the type of a variable or parameter, or the return type
+/// of a function. Type names are not keywords, so `name` is an identifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeClauseSyntax {
+    pub colon_token: Token,
+    pub identifier_token: Token,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterSyntax {
+    pub identifier_token: Token,
+    /// Only ever missing from the parameters of a lambda.
+    pub type_clause: Option<TypeClauseSyntax>,
+}
+
+/// Something that can appear at the top level of a compilation unit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberSyntax {
+    /// A function without a type clause does not return a value.
+    FunctionDeclaration {
+        function_keyword: Token,
+        identifier_token: Token,
+        left_parenthesis_token: Token,
+        parameters: SeparatedSyntaxList<ParameterSyntax>,
+        right_parenthesis_token: Token,
+        type_clause: Option<TypeClauseSyntax>,
+        body: Box<StatementSyntax>,
+    },
+    GlobalStatement {
+        statement: StatementSyntax,
+    },
+}
+
+/// The root of every syntax tree: the members of the whole input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilationUnitSyntax {
+    pub members: Vec<MemberSyntax>,
+    pub end_of_file_token: Token,
 }
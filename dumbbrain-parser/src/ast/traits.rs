@@ -1,13 +1,57 @@
 use dumbbrain::object::DumbBrainObject;
+use dumbbrain_lexer::span::Span;
 use dumbbrain_lexer::syntax::SyntaxKind;
 use dumbbrain_lexer::token::Token;
 
+use super::CompilationUnitSyntax;
+use super::ElseClauseSyntax;
 use super::ExpressionSyntax;
+use super::MemberSyntax;
+use super::ParameterSyntax;
+use super::SeparatedSyntaxList;
+use super::StatementSyntax;
+use super::TypeClauseSyntax;
 
 pub trait SyntaxNode {
     fn kind(&self) -> SyntaxKind;
     fn children(&self) -> Vec<&dyn SyntaxNode>;
     fn value(&self) -> Option<&DumbBrainObject>;
+
+    /// The source range covered by this node, from the start of its first
+    /// child to the end of its last.
+    fn span(&self) -> Span {
+        children_span(self)
+    }
+
+    /// The source text of this node including all trivia, so that the full
+    /// text of the root reproduces the parsed source exactly.
+    fn full_text(&self) -> String {
+        self.children()
+            .into_iter()
+            .map(|child| child.full_text())
+            .collect()
+    }
+}
+
+fn children_span<N: SyntaxNode + ?Sized>(node: &N) -> Span {
+    let children = node.children();
+    let first = children.first().expect("node has no children").span();
+    let last = children.last().expect("node has no children").span();
+    first.join(&last)
+}
+
+impl<T: SyntaxNode> SeparatedSyntaxList<T> {
+    /// The nodes and separators in source order.
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        let mut children: Vec<&dyn SyntaxNode> = vec![];
+        for (i, node) in self.nodes.iter().enumerate() {
+            children.push(node);
+            if let Some(separator) = self.separators.get(i) {
+                children.push(separator);
+            }
+        }
+        children
+    }
 }
 
 impl SyntaxNode for Token {
@@ -22,6 +66,14 @@ impl SyntaxNode for Token {
     fn value(&self) -> Option<&DumbBrainObject> {
         self.value.as_ref()
     }
+
+    fn span(&self) -> Span {
+        self.span.clone()
+    }
+
+    fn full_text(&self) -> String {
+        Token::full_text(self)
+    }
 }
 
 impl SyntaxNode for ExpressionSyntax {
@@ -30,7 +82,15 @@ impl SyntaxNode for ExpressionSyntax {
             ExpressionSyntax::Literal { .. } => SyntaxKind::LiteralExpression,
             ExpressionSyntax::Binary { .. } => SyntaxKind::BinaryExpression,
             ExpressionSyntax::Unary { .. } => SyntaxKind::UnaryExpression,
+            ExpressionSyntax::Name { .. } => SyntaxKind::NameExpression,
+            ExpressionSyntax::Assignment { .. } => SyntaxKind::AssignmentExpression,
+            ExpressionSyntax::CompoundAssignment { .. } => SyntaxKind::CompoundAssignmentExpression,
+            ExpressionSyntax::PrefixIncrement { .. } => SyntaxKind::PrefixIncrementExpression,
+            ExpressionSyntax::PostfixIncrement { .. } => SyntaxKind::PostfixIncrementExpression,
+            ExpressionSyntax::Lambda { .. } => SyntaxKind::LambdaExpression,
+            ExpressionSyntax::Call { .. } => SyntaxKind::CallExpression,
             ExpressionSyntax::Parenthesized { .. } => SyntaxKind::ParenthesizedExpression,
+            ExpressionSyntax::Error { .. } => SyntaxKind::ErrorExpression,
         }
     }
 
@@ -46,6 +106,51 @@ impl SyntaxNode for ExpressionSyntax {
                 operator_token,
                 right,
             } => vec![operator_token, right.as_ref()],
+            ExpressionSyntax::Name { identifier_token } => vec![identifier_token],
+            ExpressionSyntax::Assignment {
+                identifier_token,
+                equals_token,
+                expression,
+            } => vec![identifier_token, equals_token, expression.as_ref()],
+            ExpressionSyntax::CompoundAssignment {
+                identifier_token,
+                operator_token,
+                expression,
+            } => vec![identifier_token, operator_token, expression.as_ref()],
+            ExpressionSyntax::PrefixIncrement {
+                operator_token,
+                identifier_token,
+            } => vec![operator_token, identifier_token],
+            ExpressionSyntax::PostfixIncrement {
+                identifier_token,
+                operator_token,
+            } => vec![identifier_token, operator_token],
+            ExpressionSyntax::Lambda {
+                left_parenthesis_token,
+                parameters,
+                right_parenthesis_token,
+                arrow_token,
+                body,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> = vec![left_parenthesis_token];
+                children.extend(parameters.children());
+                children.push(right_parenthesis_token);
+                children.push(arrow_token);
+                children.push(body.as_ref());
+                children
+            }
+            ExpressionSyntax::Call {
+                identifier_token,
+                left_parenthesis_token,
+                arguments,
+                right_parenthesis_token,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> =
+                    vec![identifier_token, left_parenthesis_token];
+                children.extend(arguments.children());
+                children.push(right_parenthesis_token);
+                children
+            }
             ExpressionSyntax::Parenthesized {
                 left_parenthesis_token,
                 expression,
@@ -55,9 +160,218 @@ impl SyntaxNode for ExpressionSyntax {
                 expression.as_ref(),
                 right_parenthesis_token,
             ],
+            ExpressionSyntax::Error { tokens, .. } => {
+                tokens.iter().map(|t| t as &dyn SyntaxNode).collect()
+            }
+        }
+    }
+
+    fn value(&self) -> Option<&DumbBrainObject> {
+        None
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            ExpressionSyntax::Error { span, .. } => span.clone(),
+            _ => children_span(self),
+        }
+    }
+}
+
+impl SyntaxNode for StatementSyntax {
+    fn kind(&self) -> SyntaxKind {
+        match self {
+            StatementSyntax::Block { .. } => SyntaxKind::BlockStatement,
+            StatementSyntax::Expression { .. } => SyntaxKind::ExpressionStatement,
+            StatementSyntax::VariableDeclaration { .. } => SyntaxKind::VariableDeclaration,
+            StatementSyntax::If { .. } => SyntaxKind::IfStatement,
+            StatementSyntax::While { .. } => SyntaxKind::WhileStatement,
+            StatementSyntax::For { .. } => SyntaxKind::ForStatement,
+            StatementSyntax::Break { .. } => SyntaxKind::BreakStatement,
+            StatementSyntax::Continue { .. } => SyntaxKind::ContinueStatement,
+            StatementSyntax::Return { .. } => SyntaxKind::ReturnStatement,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        match self {
+            StatementSyntax::Block {
+                left_brace_token,
+                statements,
+                right_brace_token,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> = vec![left_brace_token];
+                children.extend(statements.iter().map(|s| s as &dyn SyntaxNode));
+                children.push(right_brace_token);
+                children
+            }
+            StatementSyntax::Expression { expression } => vec![expression],
+            StatementSyntax::VariableDeclaration {
+                keyword_token,
+                identifier_token,
+                type_clause,
+                equals_token,
+                initializer,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> = vec![keyword_token, identifier_token];
+                if let Some(type_clause) = type_clause {
+                    children.push(type_clause);
+                }
+                children.push(equals_token);
+                children.push(initializer.as_ref());
+                children
+            }
+            StatementSyntax::If {
+                if_keyword,
+                condition,
+                then_statement,
+                else_clause,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> =
+                    vec![if_keyword, condition, then_statement.as_ref()];
+                if let Some(else_clause) = else_clause {
+                    children.push(else_clause);
+                }
+                children
+            }
+            StatementSyntax::While {
+                while_keyword,
+                condition,
+                body,
+            } => vec![while_keyword, condition, body.as_ref()],
+            StatementSyntax::For {
+                for_keyword,
+                identifier_token,
+                equals_token,
+                lower_bound,
+                to_keyword,
+                upper_bound,
+                body,
+            } => vec![
+                for_keyword,
+                identifier_token,
+                equals_token,
+                lower_bound.as_ref(),
+                to_keyword,
+                upper_bound.as_ref(),
+                body.as_ref(),
+            ],
+            StatementSyntax::Break { break_keyword } => vec![break_keyword],
+            StatementSyntax::Continue { continue_keyword } => vec![continue_keyword],
+            StatementSyntax::Return {
+                return_keyword,
+                expression,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> = vec![return_keyword];
+                if let Some(expression) = expression {
+                    children.push(expression);
+                }
+                children
+            }
+        }
+    }
+
+    fn value(&self) -> Option<&DumbBrainObject> {
+        None
+    }
+}
+
+impl SyntaxNode for ElseClauseSyntax {
+    fn kind(&self) -> SyntaxKind {
+        SyntaxKind::ElseClause
+    }
+
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        vec![&self.else_keyword, self.else_statement.as_ref()]
+    }
+
+    fn value(&self) -> Option<&DumbBrainObject> {
+        None
+    }
+}
+
+impl SyntaxNode for TypeClauseSyntax {
+    fn kind(&self) -> SyntaxKind {
+        SyntaxKind::TypeClause
+    }
+
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        vec![&self.colon_token, &self.identifier_token]
+    }
+
+    fn value(&self) -> Option<&DumbBrainObject> {
+        None
+    }
+}
+
+impl SyntaxNode for ParameterSyntax {
+    fn kind(&self) -> SyntaxKind {
+        SyntaxKind::Parameter
+    }
+
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        let mut children: Vec<&dyn SyntaxNode> = vec![&self.identifier_token];
+        if let Some(type_clause) = &self.type_clause {
+            children.push(type_clause);
+        }
+        children
+    }
+
+    fn value(&self) -> Option<&DumbBrainObject> {
+        None
+    }
+}
+
+impl SyntaxNode for MemberSyntax {
+    fn kind(&self) -> SyntaxKind {
+        match self {
+            MemberSyntax::FunctionDeclaration { .. } => SyntaxKind::FunctionDeclaration,
+            MemberSyntax::GlobalStatement { .. } => SyntaxKind::GlobalStatement,
         }
     }
 
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        match self {
+            MemberSyntax::FunctionDeclaration {
+                function_keyword,
+                identifier_token,
+                left_parenthesis_token,
+                parameters,
+                right_parenthesis_token,
+                type_clause,
+                body,
+            } => {
+                let mut children: Vec<&dyn SyntaxNode> =
+                    vec![function_keyword, identifier_token, left_parenthesis_token];
+                children.extend(parameters.children());
+                children.push(right_parenthesis_token);
+                if let Some(type_clause) = type_clause {
+                    children.push(type_clause);
+                }
+                children.push(body.as_ref());
+                children
+            }
+            MemberSyntax::GlobalStatement { statement } => vec![statement],
+        }
+    }
+
+    fn value(&self) -> Option<&DumbBrainObject> {
+        None
+    }
+}
+
+impl SyntaxNode for CompilationUnitSyntax {
+    fn kind(&self) -> SyntaxKind {
+        SyntaxKind::CompilationUnit
+    }
+
+    fn children(&self) -> Vec<&dyn SyntaxNode> {
+        let mut children: Vec<&dyn SyntaxNode> =
+            self.members.iter().map(|m| m as &dyn SyntaxNode).collect();
+        children.push(&self.end_of_file_token);
+        children
+    }
+
     fn value(&self) -> Option<&DumbBrainObject> {
         None
     }
@@ -1,40 +1,411 @@
-use std::iter::Peekable;
-
+use ast::CompilationUnitSyntax;
+use ast::ElseClauseSyntax;
 use ast::ExpressionSyntax;
+use ast::MemberSyntax;
+use ast::ParameterSyntax;
+use ast::SeparatedSyntaxList;
+use ast::StatementSyntax;
+use ast::TypeClauseSyntax;
+use dumbbrain::diagnostic::Diagnostic;
+use dumbbrain::diagnostic::DiagnosticBag;
+use dumbbrain::diagnostic::DiagnosticCode;
 use dumbbrain_lexer::syntax::SyntaxKind;
 use dumbbrain_lexer::token::Token;
+use dumbbrain_lexer::token::Trivia;
 use dumbbrain_lexer::Lexer;
 
 pub mod ast;
 
-pub struct Parser<'s> {
-    lexer: Peekable<Lexer<'s>>,
+/// The result of parsing: a tree for the whole input, even when it contained
+/// errors, along with everything that went wrong.
+#[derive(Debug)]
+pub struct SyntaxTree {
+    pub root: CompilationUnitSyntax,
+    pub diagnostics: DiagnosticBag,
+}
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
     expected_kinds: Vec<SyntaxKind>,
-    errors: Vec<String>,
+    /// The position of the token the last error was reported at.
+    last_error_position: Option<usize>,
+    diagnostics: DiagnosticBag,
 }
 
-impl<'s> Parser<'s> {
-    pub fn new(source: &'s str) -> Self {
+impl Parser {
+    pub fn new(source: &str) -> Self {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.by_ref().collect();
         Self {
-            lexer: Lexer::new(source).peekable(),
+            tokens,
+            position: 0,
             expected_kinds: vec![],
-            errors: vec![],
+            last_error_position: None,
+            diagnostics: lexer.into_diagnostics(),
+        }
+    }
+
+    pub fn parse(mut self) -> SyntaxTree {
+        let root = self.parse_compilation_unit();
+        SyntaxTree {
+            root,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    fn parse_compilation_unit(&mut self) -> CompilationUnitSyntax {
+        let mut members = vec![];
+        while self.peek().kind != SyntaxKind::EndOfFileToken {
+            let start = self.position;
+            members.push(self.parse_member());
+            self.finish_statement(start, SyntaxKind::EndOfFileToken);
+        }
+        let end_of_file_token = self.expect(SyntaxKind::EndOfFileToken);
+        CompilationUnitSyntax {
+            members,
+            end_of_file_token,
+        }
+    }
+
+    fn parse_member(&mut self) -> MemberSyntax {
+        if self.peek().kind == SyntaxKind::FunctionKeyword {
+            return self.parse_function_declaration();
+        }
+        MemberSyntax::GlobalStatement {
+            statement: self.parse_statement(),
+        }
+    }
+
+    fn parse_function_declaration(&mut self) -> MemberSyntax {
+        let function_keyword = self.bump();
+        let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+        let left_parenthesis_token = self.expect(SyntaxKind::LeftParenthesisToken);
+        let parameters = self.parse_separated_list(Self::parse_parameter);
+        let right_parenthesis_token = self.expect(SyntaxKind::RightParenthesisToken);
+        let type_clause = if self.peek().kind == SyntaxKind::ColonToken {
+            Some(self.parse_type_clause())
+        } else {
+            None
+        };
+        let body = self.parse_block_statement();
+        MemberSyntax::FunctionDeclaration {
+            function_keyword,
+            identifier_token,
+            left_parenthesis_token,
+            parameters,
+            right_parenthesis_token,
+            type_clause,
+            body: Box::new(body),
+        }
+    }
+
+    fn parse_parameter(&mut self) -> ParameterSyntax {
+        let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+        let type_clause = Some(self.parse_type_clause());
+        ParameterSyntax {
+            identifier_token,
+            type_clause,
+        }
+    }
+
+    fn parse_lambda_parameter(&mut self) -> ParameterSyntax {
+        let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+        let type_clause = if self.peek().kind == SyntaxKind::ColonToken {
+            Some(self.parse_type_clause())
+        } else {
+            None
+        };
+        ParameterSyntax {
+            identifier_token,
+            type_clause,
+        }
+    }
+
+    fn parse_type_clause(&mut self) -> TypeClauseSyntax {
+        let colon_token = self.expect(SyntaxKind::ColonToken);
+        let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+        TypeClauseSyntax {
+            colon_token,
+            identifier_token,
+        }
+    }
+
+    /// Parses comma-separated nodes up to, but not including, a closing
+    /// parenthesis. A comma is always followed by another node.
+    fn parse_separated_list<T>(
+        &mut self,
+        mut parse_node: impl FnMut(&mut Self) -> T,
+    ) -> SeparatedSyntaxList<T> {
+        let mut list = SeparatedSyntaxList {
+            nodes: vec![],
+            separators: vec![],
+        };
+        if self.peek().kind == SyntaxKind::RightParenthesisToken {
+            return list;
+        }
+        loop {
+            list.nodes.push(parse_node(self));
+            if self.peek().kind != SyntaxKind::CommaToken {
+                break list;
+            }
+            list.separators.push(self.bump());
+        }
+    }
+
+    /// Parses statements up to, but not including, `terminator` or the end
+    /// of the input.
+    fn parse_statements(&mut self, terminator: SyntaxKind) -> Vec<StatementSyntax> {
+        let mut statements = vec![];
+        while self.peek().kind != terminator && self.peek().kind != SyntaxKind::EndOfFileToken {
+            let start = self.position;
+            statements.push(self.parse_statement());
+            self.finish_statement(start, terminator);
+        }
+        statements
+    }
+
+    /// Makes sure the statement that started at `start` was followed by a
+    /// line break or `terminator`. A token that cannot start a statement has
+    /// already been reported but not consumed, so it is skipped to make
+    /// progress.
+    fn finish_statement(&mut self, start: usize, terminator: SyntaxKind) {
+        if self.position == start {
+            self.skip_token();
+        } else {
+            self.skip_trailing_tokens(terminator);
+        }
+    }
+
+    fn parse_statement(&mut self) -> StatementSyntax {
+        match self.peek().kind {
+            SyntaxKind::LeftBraceToken => self.parse_block_statement(),
+            SyntaxKind::LetKeyword | SyntaxKind::VarKeyword => self.parse_variable_declaration(),
+            SyntaxKind::IfKeyword => self.parse_if_statement(),
+            SyntaxKind::WhileKeyword => self.parse_while_statement(),
+            SyntaxKind::ForKeyword => self.parse_for_statement(),
+            SyntaxKind::BreakKeyword => StatementSyntax::Break {
+                break_keyword: self.bump(),
+            },
+            SyntaxKind::ContinueKeyword => StatementSyntax::Continue {
+                continue_keyword: self.bump(),
+            },
+            SyntaxKind::ReturnKeyword => self.parse_return_statement(),
+            _ => StatementSyntax::Expression {
+                expression: self.parse_expression(),
+            },
+        }
+    }
+
+    fn parse_block_statement(&mut self) -> StatementSyntax {
+        let left_brace_token = self.expect(SyntaxKind::LeftBraceToken);
+        let statements = self.parse_statements(SyntaxKind::RightBraceToken);
+        let right_brace_token = self.expect(SyntaxKind::RightBraceToken);
+        StatementSyntax::Block {
+            left_brace_token,
+            statements,
+            right_brace_token,
         }
     }
 
-    pub fn parse(&mut self) -> ExpressionSyntax {
-        self.parse_expression(0)
+    fn parse_variable_declaration(&mut self) -> StatementSyntax {
+        let keyword_token = self.bump();
+        let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+        let type_clause = if self.peek().kind == SyntaxKind::ColonToken {
+            Some(self.parse_type_clause())
+        } else {
+            None
+        };
+        let equals_token = self.expect(SyntaxKind::EqualsToken);
+        let initializer = self.parse_expression();
+        StatementSyntax::VariableDeclaration {
+            keyword_token,
+            identifier_token,
+            type_clause,
+            equals_token,
+            initializer: Box::new(initializer),
+        }
     }
 
-    fn parse_expression(&mut self, parent_precedence: usize) -> ExpressionSyntax {
-        let unary_operator_precedence = self
-            .peek()
-            .map(|tok| tok.kind.unary_precedence())
-            .unwrap_or(0);
+    /// The returned value, if any, must start on the same line as `return`.
+    fn parse_return_statement(&mut self) -> StatementSyntax {
+        let return_keyword = self.bump();
+        let next = self.peek();
+        let has_expression = next.span.first_line == return_keyword.span.last_line
+            && !matches!(
+                next.kind,
+                SyntaxKind::RightBraceToken | SyntaxKind::EndOfFileToken
+            );
+        let expression = if has_expression {
+            Some(self.parse_expression())
+        } else {
+            None
+        };
+        StatementSyntax::Return {
+            return_keyword,
+            expression,
+        }
+    }
+
+    /// An `else` always belongs to the closest `if`.
+    fn parse_if_statement(&mut self) -> StatementSyntax {
+        let if_keyword = self.bump();
+        let condition = self.parse_expression();
+        let then_statement = self.parse_statement();
+        let else_clause = if self.peek().kind == SyntaxKind::ElseKeyword {
+            Some(ElseClauseSyntax {
+                else_keyword: self.bump(),
+                else_statement: Box::new(self.parse_statement()),
+            })
+        } else {
+            None
+        };
+        StatementSyntax::If {
+            if_keyword,
+            condition,
+            then_statement: Box::new(then_statement),
+            else_clause,
+        }
+    }
+
+    fn parse_while_statement(&mut self) -> StatementSyntax {
+        let while_keyword = self.bump();
+        let condition = self.parse_expression();
+        let body = self.parse_statement();
+        StatementSyntax::While {
+            while_keyword,
+            condition,
+            body: Box::new(body),
+        }
+    }
+
+    fn parse_for_statement(&mut self) -> StatementSyntax {
+        let for_keyword = self.bump();
+        let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+        let equals_token = self.expect(SyntaxKind::EqualsToken);
+        let lower_bound = self.parse_expression();
+        let to_keyword = self.expect(SyntaxKind::ToKeyword);
+        let upper_bound = self.parse_expression();
+        let body = self.parse_statement();
+        StatementSyntax::For {
+            for_keyword,
+            identifier_token,
+            equals_token,
+            lower_bound: Box::new(lower_bound),
+            to_keyword,
+            upper_bound: Box::new(upper_bound),
+            body: Box::new(body),
+        }
+    }
+
+    /// An expression statement needs a line break before it, since `1 2`
+    /// would otherwise read as two statements. Reports everything between the
+    /// end of the previous statement and the end of its line as a single
+    /// diagnostic, then skips it.
+    fn skip_trailing_tokens(&mut self, terminator: SyntaxKind) {
+        let line = self.tokens[self.position - 1].span.last_line;
+        let is_trailing = |token: &Token| {
+            token.kind != terminator
+                && token.kind != SyntaxKind::EndOfFileToken
+                && token.span.first_line == line
+        };
+        let starts_keyword_statement = matches!(
+            self.peek().kind,
+            SyntaxKind::FunctionKeyword
+                | SyntaxKind::LeftBraceToken
+                | SyntaxKind::LetKeyword
+                | SyntaxKind::VarKeyword
+                | SyntaxKind::IfKeyword
+                | SyntaxKind::WhileKeyword
+                | SyntaxKind::ForKeyword
+                | SyntaxKind::BreakKeyword
+                | SyntaxKind::ContinueKeyword
+                | SyntaxKind::ReturnKeyword
+        );
+        if starts_keyword_statement || !is_trailing(self.peek()) {
+            return;
+        }
+
+        let first_span = self.peek().span.clone();
+        let mut last_span = first_span.clone();
+        let mut skipped = vec![];
+        while is_trailing(self.peek()) {
+            let token = self.peek();
+            skipped.push(format!("{:?} `{}`", token.kind, token.text));
+            last_span = token.span.clone();
+            self.skip_token();
+        }
+        self.diagnostics.report(Diagnostic::error(
+            DiagnosticCode::TrailingInput,
+            first_span.join(&last_span),
+            format!("unexpected trailing input: {}", skipped.join(", ")),
+        ));
+    }
+
+    /// Consumes the next token and keeps it as trivia on the token after it,
+    /// so that the tree still covers the whole source.
+    fn skip_token(&mut self) {
+        let mut token = self.bump();
+        let mut skipped_trivia = token.leading_trivia;
+        skipped_trivia.push(Trivia {
+            kind: SyntaxKind::SkippedTokensTrivia,
+            position: token.position,
+            text: token.text,
+            span: token.span,
+        });
+        skipped_trivia.append(&mut token.trailing_trivia);
+
+        let next_token = &mut self.tokens[self.position];
+        skipped_trivia.append(&mut next_token.leading_trivia);
+        next_token.leading_trivia = skipped_trivia;
+    }
+
+    fn parse_expression(&mut self) -> ExpressionSyntax {
+        self.parse_assignment_expression()
+    }
+
+    /// Assignment is right-associative, so `a = b = 5` assigns `5` to `b`
+    /// first.
+    fn parse_assignment_expression(&mut self) -> ExpressionSyntax {
+        if self.peek().kind == SyntaxKind::IdentifierToken
+            && self.peek_at(1).kind == SyntaxKind::EqualsToken
+        {
+            let identifier_token = self.bump();
+            let equals_token = self.bump();
+            let expression = self.parse_assignment_expression();
+            return ExpressionSyntax::Assignment {
+                identifier_token,
+                equals_token,
+                expression: Box::new(expression),
+            };
+        }
+        if self.peek().kind == SyntaxKind::IdentifierToken
+            && matches!(
+                self.peek_at(1).kind,
+                SyntaxKind::PlusEqualsToken
+                    | SyntaxKind::MinusEqualsToken
+                    | SyntaxKind::StarEqualsToken
+                    | SyntaxKind::SlashEqualsToken
+            )
+        {
+            let identifier_token = self.bump();
+            let operator_token = self.bump();
+            let expression = self.parse_assignment_expression();
+            return ExpressionSyntax::CompoundAssignment {
+                identifier_token,
+                operator_token,
+                expression: Box::new(expression),
+            };
+        }
+        self.parse_binary_expression(0)
+    }
+
+    fn parse_binary_expression(&mut self, parent_precedence: usize) -> ExpressionSyntax {
+        let unary_operator_precedence = self.peek().kind.unary_precedence();
         let mut left =
             if unary_operator_precedence != 0 && unary_operator_precedence >= parent_precedence {
-                let operator_token = self.bump().unwrap();
-                let operand = self.parse_expression(unary_operator_precedence);
+                let operator_token = self.bump();
+                let operand = self.parse_binary_expression(unary_operator_precedence);
                 ExpressionSyntax::Unary {
                     operator_token,
                     right: Box::new(operand),
@@ -44,15 +415,18 @@ impl<'s> Parser<'s> {
             };
 
         loop {
-            let precedence = self
-                .peek()
-                .map(|tok| tok.kind.binary_precedence())
-                .unwrap_or(0);
+            let precedence = self.peek().kind.binary_precedence();
             if precedence == 0 || precedence <= parent_precedence {
                 break left;
             }
-            let operator_token = self.bump().unwrap();
-            let right = self.parse_expression(precedence);
+            let operator_token = self.bump();
+            // A right-associative operator lets its right operand continue
+            // with operators of the same precedence.
+            let right = if operator_token.kind.is_right_associative() {
+                self.parse_binary_expression(precedence - 1)
+            } else {
+                self.parse_binary_expression(precedence)
+            };
             left = ExpressionSyntax::Binary {
                 left: Box::new(left),
                 operator_token,
@@ -64,69 +438,192 @@ impl<'s> Parser<'s> {
     fn parse_primary_expression(&mut self) -> ExpressionSyntax {
         if self.check(&[
             SyntaxKind::NumberToken,
+            SyntaxKind::StringToken,
             SyntaxKind::TrueKeyword,
             SyntaxKind::FalseKeyword,
         ]) {
-            let literal_token = self.bump().unwrap();
+            let literal_token = self.bump();
             ExpressionSyntax::Literal { literal_token }
+        } else if self.check(&[SyntaxKind::IdentifierToken]) {
+            let identifier_token = self.bump();
+            // A parenthesis on the next line starts a new statement instead.
+            let next = self.peek();
+            if next.kind == SyntaxKind::LeftParenthesisToken
+                && next.span.first_line == identifier_token.span.last_line
+            {
+                return self.parse_call_expression(identifier_token);
+            }
+            if self.check(&[SyntaxKind::PlusPlusToken, SyntaxKind::MinusMinusToken]) {
+                let operator_token = self.bump();
+                return ExpressionSyntax::PostfixIncrement {
+                    identifier_token,
+                    operator_token,
+                };
+            }
+            ExpressionSyntax::Name { identifier_token }
+        } else if self.check(&[SyntaxKind::PlusPlusToken, SyntaxKind::MinusMinusToken]) {
+            let operator_token = self.bump();
+            // `--` is always lexed as one token, so `--5` is a decrement
+            // rather than two negations.
+            if !self.check(&[SyntaxKind::IdentifierToken]) {
+                let sign = &operator_token.text[..1];
+                self.error_with_note(format!(
+                    "'{}' only applies to variables; write '{}({}...)' to apply '{}' twice",
+                    operator_token.text, sign, sign, sign
+                ));
+            }
+            let identifier_token = self.expect(SyntaxKind::IdentifierToken);
+            ExpressionSyntax::PrefixIncrement {
+                operator_token,
+                identifier_token,
+            }
         } else if self.check(&[SyntaxKind::LeftParenthesisToken]) {
-            let left_parenthesis_token = self.bump().unwrap();
-            let expression = self.parse();
-            let right_parenthesis_token = self.expect(SyntaxKind::RightParenthesisToken).unwrap();
+            if self.is_at_lambda() {
+                return self.parse_lambda_expression();
+            }
+            let left_parenthesis_token = self.bump();
+            let expression = self.parse_expression();
+            let right_parenthesis_token = self.expect(SyntaxKind::RightParenthesisToken);
             ExpressionSyntax::Parenthesized {
                 left_parenthesis_token,
                 expression: Box::new(expression),
                 right_parenthesis_token,
             }
         } else {
-            panic!("unexpected: {:#?}", self.peek());
+            self.error();
+            // A closing parenthesis or brace most likely belongs to an
+            // enclosing expression or block, so leave it for that to match.
+            if self.check(&[
+                SyntaxKind::RightParenthesisToken,
+                SyntaxKind::RightBraceToken,
+                SyntaxKind::EndOfFileToken,
+            ]) {
+                self.expected_kinds.clear();
+                return ExpressionSyntax::Error {
+                    tokens: vec![],
+                    span: self.peek().span.start(),
+                };
+            }
+            let token = self.bump();
+            ExpressionSyntax::Error {
+                span: token.span.clone(),
+                tokens: vec![token],
+            }
+        }
+    }
+
+    /// Whether the parenthesis at the current token closes right before an
+    /// arrow, so that it starts the parameters of a lambda.
+    fn is_at_lambda(&self) -> bool {
+        let mut depth = 0;
+        let mut offset = 0;
+        loop {
+            match self.peek_at(offset).kind {
+                SyntaxKind::LeftParenthesisToken => depth += 1,
+                SyntaxKind::RightParenthesisToken => depth -= 1,
+                SyntaxKind::EndOfFileToken => return false,
+                _ => {}
+            }
+            offset += 1;
+            if depth == 0 {
+                return self.peek_at(offset).kind == SyntaxKind::EqualsGreaterToken;
+            }
+        }
+    }
+
+    fn parse_lambda_expression(&mut self) -> ExpressionSyntax {
+        let left_parenthesis_token = self.bump();
+        let parameters = self.parse_separated_list(Self::parse_lambda_parameter);
+        let right_parenthesis_token = self.expect(SyntaxKind::RightParenthesisToken);
+        let arrow_token = self.expect(SyntaxKind::EqualsGreaterToken);
+        let body = if self.peek().kind == SyntaxKind::LeftBraceToken {
+            self.parse_block_statement()
+        } else {
+            StatementSyntax::Expression {
+                expression: self.parse_expression(),
+            }
+        };
+        ExpressionSyntax::Lambda {
+            left_parenthesis_token,
+            parameters,
+            right_parenthesis_token,
+            arrow_token,
+            body: Box::new(body),
         }
     }
 
-    fn peek(&mut self) -> Option<&Token> {
-        self.eat_whitespace();
-        self.lexer.peek()
+    fn parse_call_expression(&mut self, identifier_token: Token) -> ExpressionSyntax {
+        let left_parenthesis_token = self.bump();
+        let arguments = self.parse_separated_list(Self::parse_expression);
+        let right_parenthesis_token = self.expect(SyntaxKind::RightParenthesisToken);
+        ExpressionSyntax::Call {
+            identifier_token,
+            left_parenthesis_token,
+            arguments,
+            right_parenthesis_token,
+        }
+    }
+
+    /// The next token. Once the input is exhausted this is always the
+    /// `EndOfFileToken`.
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.position + offset).min(last)]
     }
 
     fn check(&mut self, kinds: &[SyntaxKind]) -> bool {
-        self.eat_whitespace();
         for kind in kinds {
             self.expected_kinds.push(*kind);
         }
-        self.lexer.peek().map_or(false, |t| kinds.contains(&t.kind))
+        kinds.contains(&self.peek().kind)
     }
 
-    fn expect(&mut self, kind: SyntaxKind) -> Option<Token> {
-        let token = self.bump()?;
+    /// Consumes the next token if it has the given kind. Otherwise reports an
+    /// error and returns a missing token in its place, leaving the input
+    /// untouched so that parsing can continue.
+    fn expect(&mut self, kind: SyntaxKind) -> Token {
+        if self.check(&[kind]) {
+            return self.bump();
+        }
 
-        (token.kind == kind).then(|| token).or_else(|| {
-            self.error();
-            None
-        })
+        self.error();
+        self.expected_kinds.clear();
+        let token = self.peek();
+        Token::missing(kind, token.position, token.span.start())
     }
 
-    fn bump(&mut self) -> Option<Token> {
-        self.eat_whitespace();
+    fn bump(&mut self) -> Token {
         self.expected_kinds.clear();
-        self.lexer.next()
+        let token = self.peek().clone();
+        self.position += 1;
+        token
     }
 
-    fn eat_whitespace(&mut self) {
-        while let Some(Token {
-            kind: SyntaxKind::WhitespaceToken,
-            ..
-        }) = self.lexer.peek()
-        {
-            self.lexer.next();
-        }
+    /// Reports that the next token is not one of the kinds checked for since
+    /// the last token was consumed. Does not consume anything. Only the first
+    /// error at each token is reported, since later ones are usually caused by
+    /// the first.
+    fn error(&mut self) {
+        self.report_error(None);
     }
 
-    fn error(&mut self) {
-        let token = self.bump().unwrap();
-        let mut message = format!(
-            "at {}:{}: expected ",
-            token.span.first_line, token.span.first_column
-        );
+    /// Like `error`, with a note on how to fix the input.
+    fn error_with_note(&mut self, note: String) {
+        self.report_error(Some(note));
+    }
+
+    fn report_error(&mut self, note: Option<String>) {
+        if self.last_error_position == Some(self.position) {
+            return;
+        }
+        self.last_error_position = Some(self.position);
+
+        let found = self.peek().kind;
+        let mut message = String::from("expected ");
         for (i, kind) in self.expected_kinds.iter().enumerate() {
             if i == 0 {
                 message.push_str(&format!("{:?}", kind));
@@ -136,8 +633,17 @@ impl<'s> Parser<'s> {
                 message.push_str(&format!(", {:?}", kind));
             }
         }
+        message.push_str(&format!(", found {:?}", found));
 
-        self.errors.push(message);
+        let mut diagnostic = Diagnostic::error(
+            DiagnosticCode::UnexpectedToken,
+            self.peek().span.clone(),
+            message,
+        );
+        if let Some(note) = note {
+            diagnostic = diagnostic.with_note(note);
+        }
+        self.diagnostics.report(diagnostic);
     }
 }
 
@@ -148,7 +654,6 @@ mod tests {
     use ptree::TreeBuilder;
 
     use crate::ast::traits::SyntaxNode;
-    use crate::ast::ExpressionSyntax;
 
     use super::Parser;
 
@@ -173,28 +678,43 @@ mod tests {
         }
     }
 
-    fn format_tree(expression: &ExpressionSyntax) -> String {
+    fn format_tree(root: &dyn SyntaxNode) -> String {
         let mut builder = TreeBuilder::new("ParseTree".into());
-        build_tree(&mut builder, expression);
+        build_tree(&mut builder, root);
         let mut output = Vec::<u8>::new();
         ptree::write_tree(&builder.build(), &mut output).unwrap();
         String::from_utf8(output).unwrap()
     }
 
     fn check(input: &str, expected: Expect) {
-        let expression = Parser::new(input).parse();
-        let pretty_tree = format_tree(&expression);
+        let tree = Parser::new(input).parse();
+        assert!(tree.diagnostics.is_empty(), "{:#?}", tree.diagnostics);
+        let pretty_tree = format_tree(&tree.root);
         expected.assert_eq(&pretty_tree);
     }
 
+    fn check_errors(input: &str, expected: Expect) {
+        let tree = Parser::new(input).parse();
+        let mut output = format_tree(&tree.root);
+        for diagnostic in &tree.diagnostics {
+            output.push_str(&diagnostic.to_string());
+            output.push('\n');
+        }
+        expected.assert_eq(&output);
+    }
+
     #[test]
     fn parse_number() {
         check(
             "3",
             expect![[r#"
             ParseTree
-            └─ LiteralExpression
-               └─ NumberToken 3
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ NumberToken 3
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -205,12 +725,16 @@ mod tests {
             "1+2",
             expect![[r#"
             ParseTree
-            └─ BinaryExpression
-               ├─ LiteralExpression
-               │  └─ NumberToken 1
-               ├─ PlusToken
-               └─ LiteralExpression
-                  └─ NumberToken 2
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        ├─ PlusToken
+               │        └─ LiteralExpression
+               │           └─ NumberToken 2
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -221,8 +745,12 @@ mod tests {
             "  1",
             expect![[r#"
             ParseTree
-            └─ LiteralExpression
-               └─ NumberToken 1
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ NumberToken 1
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -233,8 +761,12 @@ mod tests {
             "123   ",
             expect![[r#"
             ParseTree
-            └─ LiteralExpression
-               └─ NumberToken 123
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ NumberToken 123
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -245,8 +777,12 @@ mod tests {
             "  4 ",
             expect![[r#"
             ParseTree
-            └─ LiteralExpression
-               └─ NumberToken 4
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ NumberToken 4
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -257,16 +793,20 @@ mod tests {
             "1 + 2 + 3",
             expect![[r#"
             ParseTree
-            └─ BinaryExpression
-               ├─ BinaryExpression
-               │  ├─ LiteralExpression
-               │  │  └─ NumberToken 1
-               │  ├─ PlusToken
-               │  └─ LiteralExpression
-               │     └─ NumberToken 2
-               ├─ PlusToken
-               └─ LiteralExpression
-                  └─ NumberToken 3
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ BinaryExpression
+               │        │  ├─ LiteralExpression
+               │        │  │  └─ NumberToken 1
+               │        │  ├─ PlusToken
+               │        │  └─ LiteralExpression
+               │        │     └─ NumberToken 2
+               │        ├─ PlusToken
+               │        └─ LiteralExpression
+               │           └─ NumberToken 3
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -277,16 +817,20 @@ mod tests {
             "1 - 2 - 3",
             expect![[r#"
             ParseTree
-            └─ BinaryExpression
-               ├─ BinaryExpression
-               │  ├─ LiteralExpression
-               │  │  └─ NumberToken 1
-               │  ├─ MinusToken
-               │  └─ LiteralExpression
-               │     └─ NumberToken 2
-               ├─ MinusToken
-               └─ LiteralExpression
-                  └─ NumberToken 3
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ BinaryExpression
+               │        │  ├─ LiteralExpression
+               │        │  │  └─ NumberToken 1
+               │        │  ├─ MinusToken
+               │        │  └─ LiteralExpression
+               │        │     └─ NumberToken 2
+               │        ├─ MinusToken
+               │        └─ LiteralExpression
+               │           └─ NumberToken 3
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -297,16 +841,20 @@ mod tests {
             "1 + 2 * 3",
             expect![[r#"
             ParseTree
-            └─ BinaryExpression
-               ├─ LiteralExpression
-               │  └─ NumberToken 1
-               ├─ PlusToken
-               └─ BinaryExpression
-                  ├─ LiteralExpression
-                  │  └─ NumberToken 2
-                  ├─ StarToken
-                  └─ LiteralExpression
-                     └─ NumberToken 3
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        ├─ PlusToken
+               │        └─ BinaryExpression
+               │           ├─ LiteralExpression
+               │           │  └─ NumberToken 2
+               │           ├─ StarToken
+               │           └─ LiteralExpression
+               │              └─ NumberToken 3
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -317,23 +865,27 @@ mod tests {
             "(((((10)))))",
             expect![[r#"
             ParseTree
-            └─ ParenthesizedExpression
-               ├─ LeftParenthesisToken
-               ├─ ParenthesizedExpression
-               │  ├─ LeftParenthesisToken
-               │  ├─ ParenthesizedExpression
-               │  │  ├─ LeftParenthesisToken
-               │  │  ├─ ParenthesizedExpression
-               │  │  │  ├─ LeftParenthesisToken
-               │  │  │  ├─ ParenthesizedExpression
-               │  │  │  │  ├─ LeftParenthesisToken
-               │  │  │  │  ├─ LiteralExpression
-               │  │  │  │  │  └─ NumberToken 10
-               │  │  │  │  └─ RightParenthesisToken
-               │  │  │  └─ RightParenthesisToken
-               │  │  └─ RightParenthesisToken
-               │  └─ RightParenthesisToken
-               └─ RightParenthesisToken
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ ParenthesizedExpression
+               │        ├─ LeftParenthesisToken
+               │        ├─ ParenthesizedExpression
+               │        │  ├─ LeftParenthesisToken
+               │        │  ├─ ParenthesizedExpression
+               │        │  │  ├─ LeftParenthesisToken
+               │        │  │  ├─ ParenthesizedExpression
+               │        │  │  │  ├─ LeftParenthesisToken
+               │        │  │  │  ├─ ParenthesizedExpression
+               │        │  │  │  │  ├─ LeftParenthesisToken
+               │        │  │  │  │  ├─ LiteralExpression
+               │        │  │  │  │  │  └─ NumberToken 10
+               │        │  │  │  │  └─ RightParenthesisToken
+               │        │  │  │  └─ RightParenthesisToken
+               │        │  │  └─ RightParenthesisToken
+               │        │  └─ RightParenthesisToken
+               │        └─ RightParenthesisToken
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -344,19 +896,23 @@ mod tests {
             "(1 + 2) * 3",
             expect![[r#"
             ParseTree
-            └─ BinaryExpression
-               ├─ ParenthesizedExpression
-               │  ├─ LeftParenthesisToken
-               │  ├─ BinaryExpression
-               │  │  ├─ LiteralExpression
-               │  │  │  └─ NumberToken 1
-               │  │  ├─ PlusToken
-               │  │  └─ LiteralExpression
-               │  │     └─ NumberToken 2
-               │  └─ RightParenthesisToken
-               ├─ StarToken
-               └─ LiteralExpression
-                  └─ NumberToken 3
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ ParenthesizedExpression
+               │        │  ├─ LeftParenthesisToken
+               │        │  ├─ BinaryExpression
+               │        │  │  ├─ LiteralExpression
+               │        │  │  │  └─ NumberToken 1
+               │        │  │  ├─ PlusToken
+               │        │  │  └─ LiteralExpression
+               │        │  │     └─ NumberToken 2
+               │        │  └─ RightParenthesisToken
+               │        ├─ StarToken
+               │        └─ LiteralExpression
+               │           └─ NumberToken 3
+               └─ EndOfFileToken
         "#]],
         )
     }
@@ -367,17 +923,1054 @@ mod tests {
             "-1 * -2",
             expect![[r#"
                 ParseTree
-                └─ BinaryExpression
-                   ├─ UnaryExpression
-                   │  ├─ MinusToken
-                   │  └─ LiteralExpression
-                   │     └─ NumberToken 1
-                   ├─ StarToken
-                   └─ UnaryExpression
-                      ├─ MinusToken
-                      └─ LiteralExpression
-                         └─ NumberToken 2
+                └─ CompilationUnit
+                   ├─ GlobalStatement
+                   │  └─ ExpressionStatement
+                   │     └─ BinaryExpression
+                   │        ├─ UnaryExpression
+                   │        │  ├─ MinusToken
+                   │        │  └─ LiteralExpression
+                   │        │     └─ NumberToken 1
+                   │        ├─ StarToken
+                   │        └─ UnaryExpression
+                   │           ├─ MinusToken
+                   │           └─ LiteralExpression
+                   │              └─ NumberToken 2
+                   └─ EndOfFileToken
             "#]],
         )
     }
+
+    #[test]
+    fn power_is_right_associative_and_binds_stronger_than_unary() {
+        check(
+            "-2 ** 3 ** -1 % 4",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ UnaryExpression
+               │        │  ├─ MinusToken
+               │        │  └─ BinaryExpression
+               │        │     ├─ LiteralExpression
+               │        │     │  └─ NumberToken 2
+               │        │     ├─ StarStarToken
+               │        │     └─ BinaryExpression
+               │        │        ├─ LiteralExpression
+               │        │        │  └─ NumberToken 3
+               │        │        ├─ StarStarToken
+               │        │        └─ UnaryExpression
+               │        │           ├─ MinusToken
+               │        │           └─ LiteralExpression
+               │        │              └─ NumberToken 1
+               │        ├─ PercentToken
+               │        └─ LiteralExpression
+               │           └─ NumberToken 4
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn missing_right_parenthesis_is_synthesized() {
+        check_errors(
+            "(1 + 2",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ ParenthesizedExpression
+               │        ├─ LeftParenthesisToken
+               │        ├─ BinaryExpression
+               │        │  ├─ LiteralExpression
+               │        │  │  └─ NumberToken 1
+               │        │  ├─ PlusToken
+               │        │  └─ LiteralExpression
+               │        │     └─ NumberToken 2
+               │        └─ RightParenthesisToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:7: expected RightParenthesisToken, found EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn unexpected_token_becomes_error_expression() {
+        check_errors(
+            "1 + @",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        ├─ PlusToken
+               │        └─ ErrorExpression
+               │           └─ BadToken
+               └─ EndOfFileToken
+            error[DB0001] at 1:5: bad character in input: '@'
+            error[DB0002] at 1:5: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found BadToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn missing_operand_before_right_parenthesis() {
+        check_errors(
+            "(1 + )",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ ParenthesizedExpression
+               │        ├─ LeftParenthesisToken
+               │        ├─ BinaryExpression
+               │        │  ├─ LiteralExpression
+               │        │  │  └─ NumberToken 1
+               │        │  ├─ PlusToken
+               │        │  └─ ErrorExpression
+               │        └─ RightParenthesisToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:6: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found RightParenthesisToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn empty_input_has_no_statements() {
+        check(
+            "",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   └─ EndOfFileToken
+            "#]],
+        )
+    }
+
+    #[test]
+    fn trailing_tokens_are_reported() {
+        check_errors(
+            "1 2 )",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ NumberToken 1
+               └─ EndOfFileToken
+            error[DB0006] at 1:3: unexpected trailing input: NumberToken `2`, RightParenthesisToken `)`
+        "#]],
+        )
+    }
+
+    #[test]
+    fn trailing_boolean_is_reported() {
+        check_errors(
+            "true false",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ TrueKeyword true
+               └─ EndOfFileToken
+            error[DB0006] at 1:6: unexpected trailing input: FalseKeyword `false`
+        "#]],
+        )
+    }
+
+    #[test]
+    fn stray_closing_tokens_are_skipped() {
+        check_errors(
+            ") }\n1",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ ErrorExpression
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ ErrorExpression
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ LiteralExpression
+               │        └─ NumberToken 1
+               └─ EndOfFileToken
+            error[DB0002] at 1:1: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found RightParenthesisToken
+            error[DB0002] at 1:3: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found RightBraceToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_statements() {
+        check(
+            "let a = 1\nvar b = a\n{ b = 2 }\nb",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ VariableDeclaration
+               │     ├─ LetKeyword
+               │     ├─ IdentifierToken
+               │     ├─ EqualsToken
+               │     └─ LiteralExpression
+               │        └─ NumberToken 1
+               ├─ GlobalStatement
+               │  └─ VariableDeclaration
+               │     ├─ VarKeyword
+               │     ├─ IdentifierToken
+               │     ├─ EqualsToken
+               │     └─ NameExpression
+               │        └─ IdentifierToken
+               ├─ GlobalStatement
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     ├─ ExpressionStatement
+               │     │  └─ AssignmentExpression
+               │     │     ├─ IdentifierToken
+               │     │     ├─ EqualsToken
+               │     │     └─ LiteralExpression
+               │     │        └─ NumberToken 2
+               │     └─ RightBraceToken
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ NameExpression
+               │        └─ IdentifierToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_if_else() {
+        check(
+            "if a < 1 { a = 1 } else a = 2",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ IfStatement
+               │     ├─ IfKeyword
+               │     ├─ BinaryExpression
+               │     │  ├─ NameExpression
+               │     │  │  └─ IdentifierToken
+               │     │  ├─ LessToken
+               │     │  └─ LiteralExpression
+               │     │     └─ NumberToken 1
+               │     ├─ BlockStatement
+               │     │  ├─ LeftBraceToken
+               │     │  ├─ ExpressionStatement
+               │     │  │  └─ AssignmentExpression
+               │     │  │     ├─ IdentifierToken
+               │     │  │     ├─ EqualsToken
+               │     │  │     └─ LiteralExpression
+               │     │  │        └─ NumberToken 1
+               │     │  └─ RightBraceToken
+               │     └─ ElseClause
+               │        ├─ ElseKeyword
+               │        └─ ExpressionStatement
+               │           └─ AssignmentExpression
+               │              ├─ IdentifierToken
+               │              ├─ EqualsToken
+               │              └─ LiteralExpression
+               │                 └─ NumberToken 2
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn else_belongs_to_closest_if() {
+        check(
+            "if a if b c else d",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ IfStatement
+               │     ├─ IfKeyword
+               │     ├─ NameExpression
+               │     │  └─ IdentifierToken
+               │     └─ IfStatement
+               │        ├─ IfKeyword
+               │        ├─ NameExpression
+               │        │  └─ IdentifierToken
+               │        ├─ ExpressionStatement
+               │        │  └─ NameExpression
+               │        │     └─ IdentifierToken
+               │        └─ ElseClause
+               │           ├─ ElseKeyword
+               │           └─ ExpressionStatement
+               │              └─ NameExpression
+               │                 └─ IdentifierToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_while() {
+        check(
+            "while true { break continue }",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ WhileStatement
+               │     ├─ WhileKeyword
+               │     ├─ LiteralExpression
+               │     │  └─ TrueKeyword true
+               │     └─ BlockStatement
+               │        ├─ LeftBraceToken
+               │        ├─ BreakStatement
+               │        │  └─ BreakKeyword
+               │        ├─ ContinueStatement
+               │        │  └─ ContinueKeyword
+               │        └─ RightBraceToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_for() {
+        check(
+            "for i = 1 to n i",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ForStatement
+               │     ├─ ForKeyword
+               │     ├─ IdentifierToken
+               │     ├─ EqualsToken
+               │     ├─ LiteralExpression
+               │     │  └─ NumberToken 1
+               │     ├─ ToKeyword
+               │     ├─ NameExpression
+               │     │  └─ IdentifierToken
+               │     └─ ExpressionStatement
+               │        └─ NameExpression
+               │           └─ IdentifierToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn for_without_to_is_reported() {
+        check_errors(
+            "for i = 1 10 {}",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ForStatement
+               │     ├─ ForKeyword
+               │     ├─ IdentifierToken
+               │     ├─ EqualsToken
+               │     ├─ LiteralExpression
+               │     │  └─ NumberToken 1
+               │     ├─ ToKeyword
+               │     ├─ LiteralExpression
+               │     │  └─ NumberToken 10
+               │     └─ BlockStatement
+               │        ├─ LeftBraceToken
+               │        └─ RightBraceToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:11: expected ToKeyword, found NumberToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_annotated_declaration() {
+        check(
+            "let string: string = string(1)",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   ├─ GlobalStatement
+                   │  └─ VariableDeclaration
+                   │     ├─ LetKeyword
+                   │     ├─ IdentifierToken
+                   │     ├─ TypeClause
+                   │     │  ├─ ColonToken
+                   │     │  └─ IdentifierToken
+                   │     ├─ EqualsToken
+                   │     └─ CallExpression
+                   │        ├─ IdentifierToken
+                   │        ├─ LeftParenthesisToken
+                   │        ├─ LiteralExpression
+                   │        │  └─ NumberToken 1
+                   │        └─ RightParenthesisToken
+                   └─ EndOfFileToken
+            "#]],
+        )
+    }
+
+    #[test]
+    fn parse_function_declaration() {
+        check(
+            "function add(a: number, b: number): number {\n  return a + b\n}",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   ├─ FunctionDeclaration
+                   │  ├─ FunctionKeyword
+                   │  ├─ IdentifierToken
+                   │  ├─ LeftParenthesisToken
+                   │  ├─ Parameter
+                   │  │  ├─ IdentifierToken
+                   │  │  └─ TypeClause
+                   │  │     ├─ ColonToken
+                   │  │     └─ IdentifierToken
+                   │  ├─ CommaToken
+                   │  ├─ Parameter
+                   │  │  ├─ IdentifierToken
+                   │  │  └─ TypeClause
+                   │  │     ├─ ColonToken
+                   │  │     └─ IdentifierToken
+                   │  ├─ RightParenthesisToken
+                   │  ├─ TypeClause
+                   │  │  ├─ ColonToken
+                   │  │  └─ IdentifierToken
+                   │  └─ BlockStatement
+                   │     ├─ LeftBraceToken
+                   │     ├─ ReturnStatement
+                   │     │  ├─ ReturnKeyword
+                   │     │  └─ BinaryExpression
+                   │     │     ├─ NameExpression
+                   │     │     │  └─ IdentifierToken
+                   │     │     ├─ PlusToken
+                   │     │     └─ NameExpression
+                   │     │        └─ IdentifierToken
+                   │     └─ RightBraceToken
+                   └─ EndOfFileToken
+            "#]],
+        )
+    }
+
+    #[test]
+    fn parse_function_without_return_type() {
+        check(
+            "function log() { return }",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ FunctionDeclaration
+               │  ├─ FunctionKeyword
+               │  ├─ IdentifierToken
+               │  ├─ LeftParenthesisToken
+               │  ├─ RightParenthesisToken
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     ├─ ReturnStatement
+               │     │  └─ ReturnKeyword
+               │     └─ RightBraceToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn return_value_must_start_on_the_same_line() {
+        check(
+            "function f() {\n  return\n  1\n}",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ FunctionDeclaration
+               │  ├─ FunctionKeyword
+               │  ├─ IdentifierToken
+               │  ├─ LeftParenthesisToken
+               │  ├─ RightParenthesisToken
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     ├─ ReturnStatement
+               │     │  └─ ReturnKeyword
+               │     ├─ ExpressionStatement
+               │     │  └─ LiteralExpression
+               │     │     └─ NumberToken 1
+               │     └─ RightBraceToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_call() {
+        check(
+            "f(1, g(), (2))",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ CallExpression
+               │        ├─ IdentifierToken
+               │        ├─ LeftParenthesisToken
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        ├─ CommaToken
+               │        ├─ CallExpression
+               │        │  ├─ IdentifierToken
+               │        │  ├─ LeftParenthesisToken
+               │        │  └─ RightParenthesisToken
+               │        ├─ CommaToken
+               │        ├─ ParenthesizedExpression
+               │        │  ├─ LeftParenthesisToken
+               │        │  ├─ LiteralExpression
+               │        │  │  └─ NumberToken 2
+               │        │  └─ RightParenthesisToken
+               │        └─ RightParenthesisToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_parenthesis_on_next_line_as_statement() {
+        check(
+            "f\n(1)",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ NameExpression
+               │        └─ IdentifierToken
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ ParenthesizedExpression
+               │        ├─ LeftParenthesisToken
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        └─ RightParenthesisToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn missing_argument_after_comma() {
+        check_errors(
+            "f(1, )",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ CallExpression
+               │        ├─ IdentifierToken
+               │        ├─ LeftParenthesisToken
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        ├─ CommaToken
+               │        ├─ ErrorExpression
+               │        └─ RightParenthesisToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:6: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found RightParenthesisToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parameter_without_type_is_reported() {
+        check_errors(
+            "function f(a) { }",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ FunctionDeclaration
+               │  ├─ FunctionKeyword
+               │  ├─ IdentifierToken
+               │  ├─ LeftParenthesisToken
+               │  ├─ Parameter
+               │  │  ├─ IdentifierToken
+               │  │  └─ TypeClause
+               │  │     ├─ ColonToken
+               │  │     └─ IdentifierToken
+               │  ├─ RightParenthesisToken
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     └─ RightBraceToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:13: expected ColonToken, found RightParenthesisToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_empty_block() {
+        check(
+            "{}",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     └─ RightBraceToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn missing_operand_before_right_brace() {
+        check_errors(
+            "{ 1 + }",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     ├─ ExpressionStatement
+               │     │  └─ BinaryExpression
+               │     │     ├─ LiteralExpression
+               │     │     │  └─ NumberToken 1
+               │     │     ├─ PlusToken
+               │     │     └─ ErrorExpression
+               │     └─ RightBraceToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:7: expected NumberToken, StringToken, TrueKeyword, FalseKeyword, IdentifierToken, PlusPlusToken, MinusMinusToken or LeftParenthesisToken, found RightBraceToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn unclosed_block_is_reported() {
+        check_errors(
+            "{ let a = 1",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ BlockStatement
+               │     ├─ LeftBraceToken
+               │     ├─ VariableDeclaration
+               │     │  ├─ LetKeyword
+               │     │  ├─ IdentifierToken
+               │     │  ├─ EqualsToken
+               │     │  └─ LiteralExpression
+               │     │     └─ NumberToken 1
+               │     └─ RightBraceToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:12: expected RightBraceToken, found EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn declaration_without_name_is_reported() {
+        check_errors(
+            "let = 1",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ VariableDeclaration
+               │     ├─ LetKeyword
+               │     ├─ IdentifierToken
+               │     ├─ EqualsToken
+               │     └─ LiteralExpression
+               │        └─ NumberToken 1
+               └─ EndOfFileToken
+            error[DB0002] at 1:5: expected IdentifierToken, found EqualsToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn logical_operator_precedence() {
+        check(
+            "true || false ^^ true && false == true",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ LiteralExpression
+               │        │  └─ TrueKeyword true
+               │        ├─ PipePipeToken
+               │        └─ BinaryExpression
+               │           ├─ LiteralExpression
+               │           │  └─ FalseKeyword false
+               │           ├─ HatHatToken
+               │           └─ BinaryExpression
+               │              ├─ LiteralExpression
+               │              │  └─ TrueKeyword true
+               │              ├─ AmpersandAmpersandToken
+               │              └─ BinaryExpression
+               │                 ├─ LiteralExpression
+               │                 │  └─ FalseKeyword false
+               │                 ├─ EqualsEqualsToken
+               │                 └─ LiteralExpression
+               │                    └─ TrueKeyword true
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn bitwise_operator_precedence() {
+        check(
+            "a | b ^ c & d == 1 << ~e + 1",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ NameExpression
+               │        │  └─ IdentifierToken
+               │        ├─ PipeToken
+               │        └─ BinaryExpression
+               │           ├─ NameExpression
+               │           │  └─ IdentifierToken
+               │           ├─ HatToken
+               │           └─ BinaryExpression
+               │              ├─ NameExpression
+               │              │  └─ IdentifierToken
+               │              ├─ AmpersandToken
+               │              └─ BinaryExpression
+               │                 ├─ NameExpression
+               │                 │  └─ IdentifierToken
+               │                 ├─ EqualsEqualsToken
+               │                 └─ BinaryExpression
+               │                    ├─ LiteralExpression
+               │                    │  └─ NumberToken 1
+               │                    ├─ LessLessToken
+               │                    └─ BinaryExpression
+               │                       ├─ UnaryExpression
+               │                       │  ├─ TildeToken
+               │                       │  └─ NameExpression
+               │                       │     └─ IdentifierToken
+               │                       ├─ PlusToken
+               │                       └─ LiteralExpression
+               │                          └─ NumberToken 1
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn logical_not_binds_stronger_than_equality() {
+        check(
+            "!true == false",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ UnaryExpression
+               │        │  ├─ BangToken
+               │        │  └─ LiteralExpression
+               │        │     └─ TrueKeyword true
+               │        ├─ EqualsEqualsToken
+               │        └─ LiteralExpression
+               │           └─ FalseKeyword false
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_string_concatenation() {
+        check(
+            r#""a\tb" + "c""#,
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ LiteralExpression
+               │        │  └─ StringToken "a\tb"
+               │        ├─ PlusToken
+               │        └─ LiteralExpression
+               │           └─ StringToken "c"
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_name() {
+        check(
+            "a + b",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ NameExpression
+               │        │  └─ IdentifierToken
+               │        ├─ PlusToken
+               │        └─ NameExpression
+               │           └─ IdentifierToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn assignment_is_right_associative() {
+        check(
+            "a = b = 5",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ AssignmentExpression
+               │        ├─ IdentifierToken
+               │        ├─ EqualsToken
+               │        └─ AssignmentExpression
+               │           ├─ IdentifierToken
+               │           ├─ EqualsToken
+               │           └─ LiteralExpression
+               │              └─ NumberToken 5
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn assignment_binds_loosest() {
+        check(
+            "a = 1 + (b = 2)",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ AssignmentExpression
+               │        ├─ IdentifierToken
+               │        ├─ EqualsToken
+               │        └─ BinaryExpression
+               │           ├─ LiteralExpression
+               │           │  └─ NumberToken 1
+               │           ├─ PlusToken
+               │           └─ ParenthesizedExpression
+               │              ├─ LeftParenthesisToken
+               │              ├─ AssignmentExpression
+               │              │  ├─ IdentifierToken
+               │              │  ├─ EqualsToken
+               │              │  └─ LiteralExpression
+               │              │     └─ NumberToken 2
+               │              └─ RightParenthesisToken
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
+
+    #[test]
+    fn parse_compound_assignment() {
+        check(
+            "a *= b -= 2 + c",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   ├─ GlobalStatement
+                   │  └─ ExpressionStatement
+                   │     └─ CompoundAssignmentExpression
+                   │        ├─ IdentifierToken
+                   │        ├─ StarEqualsToken
+                   │        └─ CompoundAssignmentExpression
+                   │           ├─ IdentifierToken
+                   │           ├─ MinusEqualsToken
+                   │           └─ BinaryExpression
+                   │              ├─ LiteralExpression
+                   │              │  └─ NumberToken 2
+                   │              ├─ PlusToken
+                   │              └─ NameExpression
+                   │                 └─ IdentifierToken
+                   └─ EndOfFileToken
+            "#]],
+        )
+    }
+
+    #[test]
+    fn parse_prefix_and_postfix_increments() {
+        check(
+            "-++a * b-- ** 2",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   ├─ GlobalStatement
+                   │  └─ ExpressionStatement
+                   │     └─ BinaryExpression
+                   │        ├─ UnaryExpression
+                   │        │  ├─ MinusToken
+                   │        │  └─ PrefixIncrementExpression
+                   │        │     ├─ PlusPlusToken
+                   │        │     └─ IdentifierToken
+                   │        ├─ StarToken
+                   │        └─ BinaryExpression
+                   │           ├─ PostfixIncrementExpression
+                   │           │  ├─ IdentifierToken
+                   │           │  └─ MinusMinusToken
+                   │           ├─ StarStarToken
+                   │           └─ LiteralExpression
+                   │              └─ NumberToken 2
+                   └─ EndOfFileToken
+            "#]],
+        )
+    }
+
+    #[test]
+    fn increments_only_apply_to_names() {
+        check_errors(
+            "++1 + a",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ PrefixIncrementExpression
+               │        ├─ PlusPlusToken
+               │        └─ IdentifierToken
+               └─ EndOfFileToken
+            error[DB0002] at 1:3: expected IdentifierToken, found NumberToken
+              = note: '++' only applies to variables; write '+(+...)' to apply '+' twice
+            error[DB0006] at 1:3: unexpected trailing input: NumberToken `1`, PlusToken `+`, IdentifierToken `a`
+        "#]],
+        )
+    }
+
+    #[test]
+    fn double_minus_is_a_decrement() {
+        check_errors(
+            "--5",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   ├─ GlobalStatement
+                   │  └─ ExpressionStatement
+                   │     └─ PrefixIncrementExpression
+                   │        ├─ MinusMinusToken
+                   │        └─ IdentifierToken
+                   └─ EndOfFileToken
+                error[DB0002] at 1:3: expected IdentifierToken, found NumberToken
+                  = note: '--' only applies to variables; write '-(-...)' to apply '-' twice
+                error[DB0006] at 1:3: unexpected trailing input: NumberToken `5`
+            "#]],
+        )
+    }
+
+    #[test]
+    fn parse_lambdas() {
+        check(
+            "let f = (x, y: int) => x * (y + 1)\nlet g = () => { return 1 }",
+            expect![[r#"
+                ParseTree
+                └─ CompilationUnit
+                   ├─ GlobalStatement
+                   │  └─ VariableDeclaration
+                   │     ├─ LetKeyword
+                   │     ├─ IdentifierToken
+                   │     ├─ EqualsToken
+                   │     └─ LambdaExpression
+                   │        ├─ LeftParenthesisToken
+                   │        ├─ Parameter
+                   │        │  └─ IdentifierToken
+                   │        ├─ CommaToken
+                   │        ├─ Parameter
+                   │        │  ├─ IdentifierToken
+                   │        │  └─ TypeClause
+                   │        │     ├─ ColonToken
+                   │        │     └─ IdentifierToken
+                   │        ├─ RightParenthesisToken
+                   │        ├─ EqualsGreaterToken
+                   │        └─ ExpressionStatement
+                   │           └─ BinaryExpression
+                   │              ├─ NameExpression
+                   │              │  └─ IdentifierToken
+                   │              ├─ StarToken
+                   │              └─ ParenthesizedExpression
+                   │                 ├─ LeftParenthesisToken
+                   │                 ├─ BinaryExpression
+                   │                 │  ├─ NameExpression
+                   │                 │  │  └─ IdentifierToken
+                   │                 │  ├─ PlusToken
+                   │                 │  └─ LiteralExpression
+                   │                 │     └─ NumberToken 1
+                   │                 └─ RightParenthesisToken
+                   ├─ GlobalStatement
+                   │  └─ VariableDeclaration
+                   │     ├─ LetKeyword
+                   │     ├─ IdentifierToken
+                   │     ├─ EqualsToken
+                   │     └─ LambdaExpression
+                   │        ├─ LeftParenthesisToken
+                   │        ├─ RightParenthesisToken
+                   │        ├─ EqualsGreaterToken
+                   │        └─ BlockStatement
+                   │           ├─ LeftBraceToken
+                   │           ├─ ReturnStatement
+                   │           │  ├─ ReturnKeyword
+                   │           │  └─ LiteralExpression
+                   │           │     └─ NumberToken 1
+                   │           └─ RightBraceToken
+                   └─ EndOfFileToken
+            "#]],
+        )
+    }
+
+    #[test]
+    fn tree_reproduces_source() {
+        let inputs = [
+            "",
+            " ( 1 +2 )*  3 // done",
+            "/* a */ -1\n/* b */ == /* c */ 2\n",
+            "(1 + ",
+            "a += ++b--  - c /= 2",
+            "let f = ( x , y:int )=>{ } (a) ) =>",
+            "1 + @ 2 ) // trailing junk",
+            "{ let x = 1 } }",
+            "if x { } else\n  while y for i = 0 to",
+            "function f(a: number, b): { return a }\nf(1, 2,",
+        ];
+        for input in inputs {
+            let tree = Parser::new(input).parse();
+            assert_eq!(tree.root.full_text(), input);
+        }
+    }
+
+    #[test]
+    fn comments_are_ignored() {
+        check(
+            "1 /* plus */ + // two\n 2",
+            expect![[r#"
+            ParseTree
+            └─ CompilationUnit
+               ├─ GlobalStatement
+               │  └─ ExpressionStatement
+               │     └─ BinaryExpression
+               │        ├─ LiteralExpression
+               │        │  └─ NumberToken 1
+               │        ├─ PlusToken
+               │        └─ LiteralExpression
+               │           └─ NumberToken 2
+               └─ EndOfFileToken
+        "#]],
+        )
+    }
 }
@@ -4,7 +4,13 @@ use dialoguer::console::style;
 use dialoguer::console::Term;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Input;
+use dumbbrain::diagnostic::DiagnosticBag;
+use dumbbrain::diagnostic::Severity;
+use dumbbrain_binder::Binder;
+use dumbbrain_binder::BoundScope;
 use dumbbrain_evaluator::Evaluator;
+use dumbbrain_evaluator::Functions;
+use dumbbrain_evaluator::Variables;
 use dumbbrain_parser::ast::traits::SyntaxNode;
 use dumbbrain_parser::Parser;
 use ptree::TreeBuilder;
@@ -29,30 +35,105 @@ fn main() {
     };
     let mut input = Input::<String>::with_theme(&theme);
     input.allow_empty(true);
+    let mut scope = BoundScope::new();
+    let mut functions = Functions::new();
+    let mut variables = Variables::new();
+    // Whether to print the parse tree, and the lowered program, of each line.
+    let mut show_tree = false;
+    let mut show_lowered = false;
     loop {
         let input = input.interact_on(&Term::stdout()).unwrap_or_else(|e| {
             crit!(log, "Could not get user input: {}", e);
             process::exit(1);
         });
 
-        let mut parser = Parser::new(input.as_str());
-        let expression = parser.parse();
-        let mut tree_builder = TreeBuilder::new("Expression".into());
-        pretty_print(&mut tree_builder, &expression);
-        let tree = tree_builder.build();
-        ptree::print_tree_with(
-            &tree,
-            &ptree::PrintConfig {
-                indent: 4,
-                characters: ptree::print_config::ASCII_CHARS_PLUS.into(),
-                ..Default::default()
-            },
-        )
-        .unwrap();
-        let bound_tree = dumbbrain_binder::Binder::bind_expression(&expression);
-        let value = Evaluator::new(bound_tree).evaluate();
-        if let Some(value) = value {
-            println!("==> {}", value);
+        match input.trim() {
+            ":vars" => {
+                print_variables(&scope, &variables);
+                continue;
+            }
+            ":tree" => {
+                show_tree = !show_tree;
+                println!(
+                    "{} parse trees",
+                    if show_tree { "showing" } else { "hiding" }
+                );
+                continue;
+            }
+            ":lowered" => {
+                show_lowered = !show_lowered;
+                println!(
+                    "{} lowered programs",
+                    if show_lowered { "showing" } else { "hiding" }
+                );
+                continue;
+            }
+            _ => {}
+        }
+
+        let syntax_tree = Parser::new(input.as_str()).parse();
+        if show_tree {
+            let mut tree_builder = TreeBuilder::new("Program".into());
+            pretty_print(&mut tree_builder, &syntax_tree.root);
+            let tree = tree_builder.build();
+            ptree::print_tree_with(
+                &tree,
+                &ptree::PrintConfig {
+                    indent: 4,
+                    characters: ptree::print_config::ASCII_CHARS_PLUS.into(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        if !syntax_tree.diagnostics.is_empty() {
+            print_diagnostics(&syntax_tree.diagnostics);
+            continue;
+        }
+        // Each line gets its own scope, so it may redeclare variables from
+        // earlier lines. They only become visible to later lines once the
+        // whole line has evaluated successfully.
+        let mut binder = Binder::with_scope(BoundScope::with_parent(scope.clone()));
+        let program = binder.bind_compilation_unit(&syntax_tree.root);
+        print_diagnostics(binder.diagnostics());
+        if binder.diagnostics().has_errors() {
+            continue;
+        }
+        if show_lowered {
+            for (function, body) in &program.functions {
+                print!("{}\n{}", function, body);
+            }
+            print!("{}", program.statement);
+        }
+        let line_scope = binder.into_scope();
+        functions.extend(program.functions);
+        match Evaluator::new(&functions, &mut variables).evaluate(&program.statement) {
+            Ok(value) => {
+                scope = line_scope;
+                if let Some(value) = value {
+                    println!("==> {}", value);
+                }
+            }
+            Err(error) => println!("{}", style(error).red()),
+        }
+    }
+}
+
+fn print_diagnostics(diagnostics: &DiagnosticBag) {
+    for diagnostic in diagnostics {
+        match diagnostic.severity {
+            Severity::Error => println!("{}", style(diagnostic).red()),
+            Severity::Warning => println!("{}", style(diagnostic).yellow()),
+        }
+    }
+}
+
+/// Lists the variables declared on earlier lines, with their values.
+fn print_variables(scope: &BoundScope, variables: &Variables) {
+    for variable in scope.visible_variables() {
+        match variables.get(variable) {
+            Some(value) => println!("{} = {}", variable, value),
+            None => println!("{}", variable),
         }
     }
 }
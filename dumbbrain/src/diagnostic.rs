@@ -0,0 +1,224 @@
+use std::fmt::Display;
+
+use crate::span::Span;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Error => "error",
+                Self::Warning => "warning",
+            }
+        )
+    }
+}
+
+/// Identifies the kind of problem a diagnostic describes, independently of
+/// its message. Codes are never reused once assigned.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DiagnosticCode {
+    BadCharacter,
+    UnexpectedToken,
+    UndefinedUnaryOperator,
+    UndefinedBinaryOperator,
+    TypeMismatch,
+    TrailingInput,
+    InvalidNumber,
+    UnterminatedString,
+    InvalidEscape,
+    UnterminatedComment,
+    UndefinedName,
+    AlreadyDeclared,
+    ReadOnlyAssignment,
+    NotInsideLoop,
+    UndefinedType,
+    WrongArgumentCount,
+    InvalidReturn,
+    MissingReturn,
+    StackOverflow,
+    UninitializedVariable,
+    UnreachableCode,
+    DivisionByZero,
+    ConstantCondition,
+    UserError,
+    CannotConvert,
+    Overflow,
+    FloatEquality,
+    CannotInfer,
+    MisplacedLambda,
+    CapturedLocal,
+    NegativeExponent,
+}
+
+impl DiagnosticCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BadCharacter => "DB0001",
+            Self::UnexpectedToken => "DB0002",
+            Self::UndefinedUnaryOperator => "DB0003",
+            Self::UndefinedBinaryOperator => "DB0004",
+            Self::TypeMismatch => "DB0005",
+            Self::TrailingInput => "DB0006",
+            Self::InvalidNumber => "DB0007",
+            Self::UnterminatedString => "DB0008",
+            Self::InvalidEscape => "DB0009",
+            Self::UnterminatedComment => "DB0010",
+            Self::UndefinedName => "DB0011",
+            Self::AlreadyDeclared => "DB0012",
+            Self::ReadOnlyAssignment => "DB0013",
+            Self::NotInsideLoop => "DB0014",
+            Self::UndefinedType => "DB0015",
+            Self::WrongArgumentCount => "DB0016",
+            Self::InvalidReturn => "DB0017",
+            Self::MissingReturn => "DB0018",
+            Self::StackOverflow => "DB0019",
+            Self::UninitializedVariable => "DB0020",
+            Self::UnreachableCode => "DB0021",
+            Self::DivisionByZero => "DB0022",
+            Self::ConstantCondition => "DB0023",
+            Self::UserError => "DB0024",
+            Self::CannotConvert => "DB0025",
+            Self::Overflow => "DB0026",
+            Self::FloatEquality => "DB0027",
+            Self::CannotInfer => "DB0028",
+            Self::MisplacedLambda => "DB0029",
+            Self::CapturedLocal => "DB0030",
+            Self::NegativeExponent => "DB0031",
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A secondary location that helps explain a diagnostic.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: DiagnosticCode, span: Span, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message,
+            span,
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn warning(code: DiagnosticCode, span: Span, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(code, span, message)
+        }
+    }
+
+    pub fn with_label(mut self, span: Span, message: String) -> Self {
+        self.labels.push(Label { span, message });
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] at {}: {}",
+            self.severity, self.code, self.span, self.message
+        )?;
+        for label in &self.labels {
+            write!(f, "\n  {}: {}", label.span, label.message)?;
+        }
+        for note in &self.notes {
+            write!(f, "\n  = note: {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the diagnostics reported by every stage of the pipeline, in the
+/// order they were reported.
+#[derive(Debug, Default, Clone)]
+pub struct DiagnosticBag {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticBag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self, other: DiagnosticBag) {
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+}
+
+impl IntoIterator for DiagnosticBag {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DiagnosticBag {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.iter()
+    }
+}
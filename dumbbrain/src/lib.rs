@@ -1,2 +1,4 @@
+pub mod diagnostic;
 pub mod object;
+pub mod span;
 pub mod types;
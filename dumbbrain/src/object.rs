@@ -2,10 +2,25 @@ use dumbbrain_macros::IsAs;
 
 use std::fmt::Display;
 
+use crate::types::DumbBrainType;
+
 #[derive(Debug, PartialEq, Clone, IsAs)]
 pub enum DumbBrainObject {
-    Number(f64),
+    Int(i64),
+    Float(f64),
     Boolean(bool),
+    String(String),
+}
+
+impl DumbBrainObject {
+    pub fn kind(&self) -> DumbBrainType {
+        match self {
+            Self::Int(_) => DumbBrainType::Int,
+            Self::Float(_) => DumbBrainType::Float,
+            Self::Boolean(_) => DumbBrainType::Boolean,
+            Self::String(_) => DumbBrainType::String,
+        }
+    }
 }
 
 impl Display for DumbBrainObject {
@@ -14,9 +29,42 @@ impl Display for DumbBrainObject {
             f,
             "{}",
             match self {
-                Self::Number(n) => n.to_string(),
+                Self::Int(n) => n.to_string(),
+                // `Debug` keeps the `.0` on whole numbers, so that they read
+                // back as floats.
+                Self::Float(n) => format!("{:?}", n),
                 Self::Boolean(b) => b.to_string(),
+                Self::String(s) => quote(s),
             }
         )
     }
 }
+
+/// Renders a string the way it would be written as a literal, so that the
+/// REPL output can be pasted back in.
+fn quote(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_quotes_strings() {
+        let s = DumbBrainObject::String(String::from("say \"hi\"\n\t\\ \u{7}é"));
+        assert_eq!(s.to_string(), r#""say \"hi\"\n\t\\ \u{7}é""#);
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Span {
+    pub first_line: usize,
+    pub first_column: usize,
+    pub last_line: usize,
+    pub last_column: usize,
+}
+
+impl Span {
+    /// A zero-width span at the start of this one.
+    pub fn start(&self) -> Self {
+        Self {
+            first_line: self.first_line,
+            first_column: self.first_column,
+            last_line: self.first_line,
+            last_column: self.first_column,
+        }
+    }
+
+    /// The span running from the start of this one to the end of `other`.
+    pub fn join(&self, other: &Span) -> Self {
+        Self {
+            first_line: self.first_line,
+            first_column: self.first_column,
+            last_line: other.last_line,
+            last_column: other.last_column,
+        }
+    }
+
+    /// A zero-width span at the end of this one.
+    pub fn end(&self) -> Self {
+        Self {
+            first_line: self.last_line,
+            first_column: self.last_column,
+            last_line: self.last_line,
+            last_column: self.last_column,
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.first_line, self.first_column)
+    }
+}
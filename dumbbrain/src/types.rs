@@ -1,5 +1,52 @@
-#[derive(Debug, PartialEq, Clone, Copy)]
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum DumbBrainType {
     Boolean,
-    Number,
+    Int,
+    Float,
+    String,
+    /// The type of calls to functions that do not return a value.
+    Void,
+    /// The type of an expression that failed to bind. Operations on it
+    /// produce no further diagnostics, so one mistake is reported once.
+    Error,
+    /// A type that the binder has not inferred yet, numbered by the binder.
+    /// It only appears while the binder infers the types of a lambda.
+    Variable(u32),
+}
+
+impl Display for DumbBrainType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Boolean => "bool",
+                Self::Int => "int",
+                Self::Float => "float",
+                Self::String => "string",
+                Self::Void => "void",
+                Self::Error | Self::Variable(_) => "?",
+            }
+        )
+    }
+}
+
+impl DumbBrainType {
+    /// The type a type clause names, if any.
+    pub fn lookup(name: &str) -> Option<Self> {
+        match name {
+            "bool" => Some(Self::Boolean),
+            "int" => Some(Self::Int),
+            // The name of the only numeric type before ints were added.
+            "float" | "number" => Some(Self::Float),
+            "string" => Some(Self::String),
+            _ => None,
+        }
+    }
+
+    pub fn is_numeric(self) -> bool {
+        matches!(self, Self::Int | Self::Float)
+    }
 }